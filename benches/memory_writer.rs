@@ -0,0 +1,54 @@
+extern crate atln_processor;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use atln_processor::emulator::memory::{Frame, Memory, PAGE_BYTES_COUNT};
+use atln_processor::number::{Data, Size, QUAD_SIZE};
+
+/// Number of times each page fill is repeated.
+const ROUNDS: u32 = 2_000;
+
+/// Quads needed to fill one page.
+const WRITES: u64 = PAGE_BYTES_COUNT / QUAD_SIZE as u64;
+
+/// Memory with 2 physical pages where the first virtual page is mapped to the second physical page.
+fn memory() -> Memory {
+    let mut memory = Memory::new_paged(vec![0u8; 2 * PAGE_BYTES_COUNT as usize]).unwrap();
+    memory.pages.insert(0, 1);
+    memory
+}
+
+/// Time filling the first virtual page over every round.
+fn measure(mut fill: impl FnMut(&mut Memory)) -> Duration {
+    let mut memory = memory();
+    let start = Instant::now();
+    for _ in 0..ROUNDS { fill(black_box(&mut memory)) }
+    let elapsed = start.elapsed();
+
+    assert!(memory.bytes[PAGE_BYTES_COUNT as usize..].iter().all(|byte| *byte == 0xAB));
+    elapsed
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let per_write = elapsed.as_nanos() as f64 / (ROUNDS as u64 * WRITES) as f64;
+    println!("{name:<8} {:>10.2?} total {per_write:>8.2} ns per write", elapsed);
+}
+
+fn main() {
+    let value = Data::Quad(0xABAB_ABAB_ABAB_ABAB);
+
+    let set = measure(|memory| {
+        for index in 0..WRITES {
+            let frame = Frame { address: index * QUAD_SIZE as u64, size: Size::Quad };
+            memory.set(frame, true, value.clone()).unwrap();
+        }
+    });
+
+    let writer = measure(|memory| {
+        let mut writer = memory.writer(0, true);
+        for _ in 0..WRITES { writer.write_number(value.clone()).unwrap() }
+    });
+
+    report("set", set);
+    report("writer", writer);
+}
@@ -31,12 +31,13 @@
 //! injecting a different page into the address and then using that new address. The item remains the same.
 
 use std::collections::HashMap;
+use std::fmt::Debug;
 use std::io;
 use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::ops::Range;
 use utility::LastError;
 use crate::number;
-use crate::number::{BYTE_SIZE, DUAL_SIZE, QUAD_SIZE, Size, WORD_SIZE};
-use crate::utility::read_vec_into_buffer;
+use crate::number::{QUAD_SIZE, Size};
 
 // region: Constants
 pub const DUAL_ALIGNED_MASK   : u64 = 0b1;
@@ -52,8 +53,78 @@ pub const PAGE_BYTES_COUNT    : u64 = (u64::MAX & PAGE_ITEM_MASK) + 1;
 //                                                                        TODO: maximum index, not the count. 
 // endregion
 
+/// Get the mask selecting the address bits that must be 0 for an address to be aligned for a size.
+fn aligned_mask(size: number::Size) -> u64 {
+    match size {
+        number::Size::Byte => 0,
+        number::Size::Word => WORD_ALIGNED_MASK,
+        number::Size::Dual => DUAL_ALIGNED_MASK,
+        number::Size::Quad => QUAD_ALIGNED_MASK
+    }
+}
+
+/// Check whether an address is aligned for a size without constructing a [Frame]. This follows the same rules as
+/// [Frame::is_aligned].
+/// ```
+/// use atln_processor::emulator::memory::{Frame, is_address_aligned};
+/// use atln_processor::number::Size;
+///
+/// assert!(is_address_aligned(7, Size::Byte));
+/// assert!(is_address_aligned(16, Size::Quad));
+/// assert!(!is_address_aligned(12, Size::Quad));
+///
+/// for size in Size::ALL {
+///     for address in 0..32 {
+///         assert_eq!(is_address_aligned(address, size.clone()), Frame { address, size: size.clone() }.is_aligned());
+///     }
+/// }
+/// ```
+pub fn is_address_aligned(address: u64, size: number::Size) -> bool {
+    address & aligned_mask(size) == 0
+}
+
+/// Get the closest aligned address for a size that is not above the address.
+/// ```
+/// use atln_processor::emulator::memory::{align_down, is_address_aligned};
+/// use atln_processor::number::Size;
+///
+/// assert_eq!(align_down(13, Size::Quad), 8);
+/// assert_eq!(align_down(16, Size::Quad), 16);
+/// assert_eq!(align_down(13, Size::Byte), 13);
+///
+/// for size in Size::ALL {
+///     assert!(is_address_aligned(align_down(u64::MAX, size.clone()), size.clone()));
+/// }
+/// ```
+pub fn align_down(address: u64, size: number::Size) -> u64 {
+    address & !aligned_mask(size)
+}
+
+/// Get the closest aligned address for a size that is not below the address. [None] is returned if that address is
+/// past the end of the address space.
+/// ```
+/// use atln_processor::emulator::memory::{align_up, is_address_aligned};
+/// use atln_processor::number::Size;
+///
+/// assert_eq!(align_up(13, Size::Quad), Some(16));
+/// assert_eq!(align_up(16, Size::Quad), Some(16));
+/// assert_eq!(align_up(13, Size::Byte), Some(13));
+/// assert_eq!(align_up(u64::MAX, Size::Quad), None);
+///
+/// for size in Size::ALL {
+///     for address in 0..32 {
+///         let aligned = align_up(address, size.clone()).unwrap();
+///         assert!(is_address_aligned(aligned, size.clone()) && aligned >= address);
+///     }
+/// }
+/// ```
+pub fn align_up(address: u64, size: number::Size) -> Option<u64> {
+    let mask = aligned_mask(size);
+    Some(address.checked_add(mask)? & !mask)
+}
+
 /// An address frame which includes a memory address and the frame size.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Frame {
     pub address: u64,
     pub size: number::Size
@@ -79,19 +150,47 @@ impl Frame {
     /// assert!(!Frame { address: 1, size: Size::Quad }.is_aligned());
     /// ```
     pub fn is_aligned(&self) -> bool {
-        let masked = match self.size {
-            number::Size::Byte => 0,
-            number::Size::Word => self.address & WORD_ALIGNED_MASK,
-            number::Size::Dual => self.address & DUAL_ALIGNED_MASK,
-            number::Size::Quad => self.address & QUAD_ALIGNED_MASK
-        };
+        is_address_aligned(self.address, self.size.clone())
+    }
 
-        masked == 0
+    /// Gets the address directly after the last targeted byte. [None] is returned if the frame reaches past the end of
+    /// the address space, as that address cannot be represented.
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+    /// use atln_processor::number::Size;
+    ///
+    /// assert_eq!(Frame { address: 8, size: Size::Quad }.max_address(), Some(16));
+    /// assert_eq!(Frame { address: u64::MAX - 7, size: Size::Quad }.max_address(), None);
+    ///
+    /// // A frame at the top of the address space is out of bounds rather than overflowing.
+    /// let mut memory = Memory::from(vec![0u8; 16]);
+    /// assert_eq!(memory.get(Frame { address: u64::MAX - 7, size: Size::Quad }, false), Err(GetError::OutOfBounds));
+    /// ```
+    pub fn max_address(&self) -> Option<u64> {
+        self.address.checked_add(self.size.size() as u64)
     }
 
-    /// Gets the largest targeted address.
-    pub fn max_address(&self) -> u64 {
-        self.address + self.size.size() as u64
+    /// Check whether this frame shares any bytes with another frame. An executor uses this to find instructions whose
+    /// source and destination overlap, where the result depends on the order of the reads and writes.
+    /// ```
+    /// use atln_processor::emulator::memory::Frame;
+    /// use atln_processor::number::Size;
+    ///
+    /// let quad = Frame { address: 8, size: Size::Quad };
+    ///
+    /// assert!(quad.overlaps(&Frame { address: 12, size: Size::Dual }));
+    /// assert!(quad.overlaps(&Frame { address: 15, size: Size::Byte }));
+    /// assert!(Frame { address: 6, size: Size::Dual }.overlaps(&quad));
+    ///
+    /// // Frames that only touch do not overlap.
+    /// assert!(!quad.overlaps(&Frame { address: 16, size: Size::Byte }));
+    /// assert!(!quad.overlaps(&Frame { address: 0, size: Size::Quad }));
+    /// ```
+    pub fn overlaps(&self, other: &Frame) -> bool {
+        let end = self.address.saturating_add(self.size.size() as u64);
+        let other_end = other.address.saturating_add(other.size.size() as u64);
+
+        self.address < other_end && other.address < end
     }
 }
 
@@ -124,6 +223,31 @@ pub trait Address {
     /// The result is used as a layer, needs to be shifted over to allow for it to layer on an item suffix. This also
     /// behaves as removing the items bits.
     fn offset_page(&self) -> u64;
+
+    /// Same as [Address::extract_item] but the number of item bits is provided rather than using [PAGE_ITEM_BITS].
+    fn extract_item_sized(&self, item_bits: u64) -> u64;
+
+    /// Same as [Address::set_item] but the number of item bits is provided rather than using [PAGE_ITEM_BITS].
+    fn set_item_sized(&self, r#virtual: u64, item_bits: u64) -> u64;
+
+    /// Same as [Address::extract_page] but the number of item bits is provided rather than using [PAGE_ITEM_BITS].
+    fn extract_page_sized(&self, item_bits: u64) -> u64;
+
+    /// Same as [Address::offset_page] but the number of item bits is provided rather than using [PAGE_ITEM_BITS].
+    fn offset_page_sized(&self, item_bits: u64) -> u64;
+}
+
+/// Create a mask which selects the item bits of an address whose pages use the given number of item bits.
+/// ```
+/// use atln_processor::emulator::memory::{page_item_mask, PAGE_ITEM_BITS, PAGE_ITEM_MASK};
+///
+/// assert_eq!(page_item_mask(PAGE_ITEM_BITS), PAGE_ITEM_MASK);
+/// assert_eq!(page_item_mask(4), 0b1111);
+/// assert_eq!(page_item_mask(0), 0);
+/// assert_eq!(page_item_mask(64), u64::MAX);
+/// ```
+pub fn page_item_mask(item_bits: u64) -> u64 {
+    u64::MAX.checked_shr((64 - item_bits.min(64)) as u32).unwrap_or(0)
 }
 
 impl Address for u64 {
@@ -131,7 +255,7 @@ impl Address for u64 {
     /// assert!(false); // TODO: Test
     /// ```
     fn extract_item(&self) -> u64 {
-        PAGE_ITEM_MASK & self
+        self.extract_item_sized(PAGE_ITEM_BITS)
     }
 
     /// ```
@@ -143,15 +267,14 @@ impl Address for u64 {
     /// assert_eq!(0b11111111_00000000_00000000_00000000_00000000_00000000_00000000_00000000.set_item(0b00000000_00000000_00000000_00000000_00000000_00000000_00000000_00001010), 0b11111111_00000000_00000000_00000000_00000000_00000000_00000000_00001010);
     /// ```
     fn set_item(&self, r#virtual: u64) -> u64 {
-        let page_item = r#virtual & PAGE_ITEM_MASK;
-        (self & PAGE_IDENTIFIER_MASK) | page_item
+        self.set_item_sized(r#virtual, PAGE_ITEM_BITS)
     }
 
     /// ```
     /// assert!(false); // TODO: Test
     /// ```
     fn extract_page(&self) -> u64 {
-        (PAGE_IDENTIFIER_MASK & self) >> PAGE_ITEM_BITS
+        self.extract_page_sized(PAGE_ITEM_BITS)
     }
 
     /// ```
@@ -165,24 +288,239 @@ impl Address for u64 {
     /// // TODO
     /// ```
     fn offset_page(&self) -> u64 {
-        self << PAGE_ITEM_BITS
+        self.offset_page_sized(PAGE_ITEM_BITS)
+    }
+
+    /// ```
+    /// use atln_processor::emulator::memory::Address;
+    ///
+    /// assert_eq!(0b1010_0110u64.extract_item_sized(4), 0b0110);
+    /// assert_eq!(0b1010_0110u64.extract_item_sized(0), 0);
+    /// ```
+    fn extract_item_sized(&self, item_bits: u64) -> u64 {
+        page_item_mask(item_bits) & self
+    }
+
+    /// ```
+    /// use atln_processor::emulator::memory::Address;
+    ///
+    /// assert_eq!(0b1010_0000u64.set_item_sized(0b1111_0110, 4), 0b1010_0110);
+    /// ```
+    fn set_item_sized(&self, r#virtual: u64, item_bits: u64) -> u64 {
+        let item_mask = page_item_mask(item_bits);
+        (self & !item_mask) | (r#virtual & item_mask)
+    }
+
+    /// ```
+    /// use atln_processor::emulator::memory::Address;
+    ///
+    /// assert_eq!(0b1010_0110u64.extract_page_sized(4), 0b1010);
+    /// assert_eq!(u64::MAX.extract_page_sized(64), 0);
+    /// ```
+    fn extract_page_sized(&self, item_bits: u64) -> u64 {
+        self.checked_shr(item_bits as u32).unwrap_or(0)
+    }
+
+    /// ```
+    /// use atln_processor::emulator::memory::Address;
+    ///
+    /// assert_eq!(0b1010u64.offset_page_sized(4), 0b1010_0000);
+    /// ```
+    fn offset_page_sized(&self, item_bits: u64) -> u64 {
+        self.checked_shl(item_bits as u32).unwrap_or(0)
     }
 }
+
+/// An address in the virtual address space. It must be translated with [Memory::translate] before it can index the
+/// memory bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Virtual(pub u64);
+
+/// An address that directly indexes the memory bytes or an attached device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Physical(pub u64);
+
+/// Implement conversions and [Address] for an address wrapper by forwarding to the wrapped [u64].
+macro_rules! address_wrapper {
+    ($wrapper: ident) => {
+        impl From<u64> for $wrapper {
+            fn from(address: u64) -> Self { $wrapper(address) }
+        }
+
+        impl From<$wrapper> for u64 {
+            fn from(address: $wrapper) -> Self { address.0 }
+        }
+
+        impl Address for $wrapper {
+            fn extract_item(&self) -> u64 { self.0.extract_item() }
+            fn set_item(&self, r#virtual: u64) -> u64 { self.0.set_item(r#virtual) }
+            fn extract_page(&self) -> u64 { self.0.extract_page() }
+            fn set_page(&self, page: u64) -> u64 { self.0.set_page(page) }
+            fn offset_page(&self) -> u64 { self.0.offset_page() }
+            fn extract_item_sized(&self, item_bits: u64) -> u64 { self.0.extract_item_sized(item_bits) }
+            fn set_item_sized(&self, r#virtual: u64, item_bits: u64) -> u64 { self.0.set_item_sized(r#virtual, item_bits) }
+            fn extract_page_sized(&self, item_bits: u64) -> u64 { self.0.extract_page_sized(item_bits) }
+            fn offset_page_sized(&self, item_bits: u64) -> u64 { self.0.offset_page_sized(item_bits) }
+        }
+    };
+}
+
+address_wrapper!(Virtual);
+address_wrapper!(Physical);
 // endregion
 
 /// Memory addressing must be aligned. Rules must be followed for frame based operations on memory.
 /// - If the memory is size constrained, then ensure the frame is not reaching past the memory size limit.
 /// - Frames must be aligned to simulate hardware limitations of an implemented memory module.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct Memory {
     pub bytes: Vec<u8>,
     pub max_address: Option<u64>,
-    /// Number of bytes in each page.
-    pub page_size: u64,
+    /// Number of right most address bits used for the page item. Each page holds 2 to the power of this many bytes.
+    /// This is [PAGE_ITEM_BITS] by default.
+    pub page_item_bits: u64,
     /// Mappings of virtual page addresses to physical page addresses.
-    pub pages: HashMap<u64, u64>
+    pub pages: HashMap<u64, u64>,
+    /// Devices which take over reads and writes to their physical address ranges. Use [Memory::attach_device] to add
+    /// devices so overlapping ranges are rejected.
+    pub devices: Vec<MappedDevice>,
+    /// Which memory bytes have been written to when poison mode is on, indexed by physical address. Poison mode is off
+    /// when this is [None], see [Memory::enable_poison].
+    pub written: Option<Vec<bool>>,
+    /// Whether frames that are not aligned can be read and written rather than causing [GetError::UnalignedFrame].
+    /// Unaligned frames are accessed a byte at a time and only reach the memory bytes, not attached devices. This is
+    /// false by default.
+    pub allow_unaligned: bool,
+    /// Whether the memory models a ROM region. Every write, including writes to attached devices, is rejected with
+    /// [GetError::ReadOnly] while reads work as normal. This is false by default.
+    pub read_only: bool
 }
 
+// region: Memory mapped devices
+/// A device such as a timer or console which is interacted with through memory addresses rather than the ports. Reads
+/// and writes to the device's address range are given to the device instead of the memory bytes.
+pub trait MemoryMappedDevice: Debug + Send {
+    /// Read from the device. The offset is relative to the start of the device's address range.
+    fn read(&mut self, offset: u64, size: Size) -> number::Data;
+
+    /// Write to the device. The offset is relative to the start of the device's address range.
+    fn write(&mut self, offset: u64, size: Size, value: number::Data);
+
+    /// Create an independent copy of the device in its current state. Used when the [Memory] it is attached to is
+    /// cloned.
+    fn clone_box(&self) -> Box<dyn MemoryMappedDevice>;
+}
+
+impl Clone for Box<dyn MemoryMappedDevice> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// A device attached to a range of physical addresses.
+#[derive(Debug, Clone)]
+pub struct MappedDevice {
+    /// First physical address of the range.
+    pub start: u64,
+    /// Number of addresses in the range.
+    pub length: u64,
+    pub device: Box<dyn MemoryMappedDevice>
+}
+
+impl MappedDevice {
+    /// Whether the address lies in the device's address range.
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.start && address - self.start < self.length
+    }
+}
+
+/// Caused by attaching a device to an invalid address range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachError {
+    /// The range has no addresses or reaches past the largest address.
+    Range,
+    /// The range shares addresses with a device that is already attached.
+    Overlapping
+}
+// endregion
+
+/// A captured copy of the memory bytes, page table and access settings. Produced by [Memory::snapshot] and consumed
+/// by [Memory::restore] to return memory to an earlier state for deterministic replay. Attached devices are not
+/// captured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySnapshot {
+    pub bytes: Vec<u8>,
+    pub max_address: Option<u64>,
+    pub page_item_bits: u64,
+    pub pages: HashMap<u64, u64>,
+    pub written: Option<Vec<bool>>,
+    pub allow_unaligned: bool,
+    pub read_only: bool
+}
+
+// region: Memory access
+/// The ways the processor interacts with memory. Implemented by [Memory], other implementations can be used in its
+/// place by processor code that only needs to read and write memory.
+/// ```
+/// use atln_processor::emulator::memory::{Frame, GetError, MemoryAccess};
+/// use atln_processor::number::Data;
+///
+/// /// Memory that reads as the low byte of the address and ignores writes.
+/// struct Pattern;
+///
+/// impl MemoryAccess for Pattern {
+///     fn get(&mut self, frame: Frame, _virtual: bool) -> Result<Data, GetError> { Ok(Data::Byte(frame.address as u8)) }
+///     fn set(&mut self, _frame: Frame, _virtual: bool, _data: Data) -> Result<(), GetError> { Ok(()) }
+///     fn translate_virtual(&self, address: u64) -> Option<u64> { Some(address) }
+///
+///     fn get_bytes(&self, address: u64, buffer: &mut [u8], _virtual: bool) -> Result<(), GetError> {
+///         for (index, byte) in buffer.iter_mut().enumerate() { *byte = (address + index as u64) as u8 }
+///         Ok(())
+///     }
+///
+///     fn set_bytes(&mut self, _address: u64, _bytes: &[u8], _virtual: bool) -> Result<(), GetError> { Ok(()) }
+/// }
+///
+/// let mut buffer = [0u8; 2];
+/// Pattern.get_bytes(7, &mut buffer, false).unwrap();
+/// assert_eq!(buffer, [7, 8]);
+/// ```
+pub trait MemoryAccess {
+    /// See [Memory::get].
+    fn get(&mut self, frame: Frame, r#virtual: bool) -> Result<number::Data, GetError>;
+    /// See [Memory::set].
+    fn set(&mut self, frame: Frame, r#virtual: bool, data: number::Data) -> Result<(), GetError>;
+    /// See [Memory::translate_virtual].
+    fn translate_virtual(&self, r#virtual: u64) -> Option<u64>;
+    /// See [Memory::get_bytes].
+    fn get_bytes(&self, address: u64, buffer: &mut [u8], r#virtual: bool) -> Result<(), GetError>;
+    /// See [Memory::set_bytes].
+    fn set_bytes(&mut self, address: u64, bytes: &[u8], r#virtual: bool) -> Result<(), GetError>;
+}
+
+impl MemoryAccess for Memory {
+    fn get(&mut self, frame: Frame, r#virtual: bool) -> Result<number::Data, GetError> {
+        Memory::get(self, frame, r#virtual)
+    }
+
+    fn set(&mut self, frame: Frame, r#virtual: bool, data: number::Data) -> Result<(), GetError> {
+        Memory::set(self, frame, r#virtual, data)
+    }
+
+    fn translate_virtual(&self, r#virtual: u64) -> Option<u64> {
+        Memory::translate_virtual(self, r#virtual)
+    }
+
+    fn get_bytes(&self, address: u64, buffer: &mut [u8], r#virtual: bool) -> Result<(), GetError> {
+        Memory::get_bytes(self, address, buffer, r#virtual)
+    }
+
+    fn set_bytes(&mut self, address: u64, bytes: &[u8], r#virtual: bool) -> Result<(), GetError> {
+        Memory::set_bytes(self, address, bytes, r#virtual)
+    }
+}
+// endregion
+
 // region: Memory cursor
 /// A tool used for interacting with memory through a [Read] and [Write] stream.
 #[derive(Debug)]
@@ -269,11 +607,88 @@ pub enum GetError {
     UnalignedFrame,
     /// The address frame crosses the positive memory boundaries.
     OutOfBounds,
-    /// Virtual memory context was in use but the remapping did not exist in the page list.
-    PageFault
+    /// Virtual memory context was in use but the remapping did not exist in the page list. The address is the virtual
+    /// address that could not be translated.
+    PageFault { address: u64 },
+    /// Poison mode is on and a byte being read has never been written to.
+    Uninitialized,
+    /// The value written with [Memory::set_checked] has significant bits that do not fit in the frame.
+    Truncated,
+    /// The memory is read only and cannot be written to, see [Memory::read_only].
+    ReadOnly
+}
+
+/// Caused by creating paged memory from bytes that do not fill a whole number of pages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PagedError {
+    /// The last page would only be partially backed by bytes.
+    PartialPage
 }
 
 impl Memory {
+    /// Create memory for use with virtual addressing. The bytes must be a whole number of pages of
+    /// [Memory::page_bytes_count] bytes, which is [PAGE_BYTES_COUNT] for new memory, otherwise accesses could fault
+    /// part way through the last page and [Err(PagedError::PartialPage)] is returned instead.
+    /// ```
+    /// use atln_processor::emulator::memory::{Memory, PAGE_BYTES_COUNT, PagedError};
+    ///
+    /// let memory = Memory::new_paged(vec![0u8; 2 * PAGE_BYTES_COUNT as usize]).unwrap();
+    /// assert_eq!(memory.page_count(), 2);
+    ///
+    /// assert_eq!(Memory::new_paged(vec![0u8; PAGE_BYTES_COUNT as usize + 1]).unwrap_err(), PagedError::PartialPage);
+    /// ```
+    pub fn new_paged(bytes: Vec<u8>) -> Result<Self, PagedError> {
+        let memory = Self::from(bytes);
+        // Any bytes past the whole pages are a partial page.
+        if memory.page_count() * memory.page_bytes_count() != memory.bytes.len() as u64 { return Err(PagedError::PartialPage) }
+        Ok(memory)
+    }
+
+    /// Get the number of bytes in a page with the current [Memory::page_item_bits].
+    /// ```
+    /// use atln_processor::emulator::memory::{Memory, PAGE_BYTES_COUNT};
+    ///
+    /// let mut memory = Memory::from(Vec::new());
+    /// assert_eq!(memory.page_bytes_count(), PAGE_BYTES_COUNT);
+    ///
+    /// memory.page_item_bits = 4;
+    /// assert_eq!(memory.page_bytes_count(), 16);
+    /// ```
+    pub fn page_bytes_count(&self) -> u64 {
+        // A page too large to count is larger than any memory.
+        1u64.checked_shl(self.page_item_bits as u32).unwrap_or(u64::MAX)
+    }
+
+    /// Get the number of whole pages the memory bytes hold with the current [Memory::page_item_bits].
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 40]);
+    /// memory.page_item_bits = 4;
+    ///
+    /// assert_eq!(memory.page_count(), 2);
+    /// ```
+    pub fn page_count(&self) -> u64 {
+        self.bytes.len() as u64 / self.page_bytes_count()
+    }
+
+    /// Iterate over the page mappings as pairs of virtual page and physical page, in ascending order of virtual page.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    ///
+    /// let mut memory = Memory::from(Vec::new());
+    /// memory.pages.insert(9, 0);
+    /// memory.pages.insert(2, 7);
+    /// memory.pages.insert(5, 1);
+    ///
+    /// assert_eq!(memory.mappings().collect::<Vec<_>>(), vec![ (2, 7), (5, 1), (9, 0) ]);
+    /// ```
+    pub fn mappings(&self) -> impl Iterator<Item=(u64, u64)> {
+        let mut mappings = self.pages.iter().map(|(r#virtual, physical)| (*r#virtual, *physical)).collect::<Vec<_>>();
+        mappings.sort_unstable();
+        mappings.into_iter()
+    }
+
     /// Translate the virtual address into a physical address based on the current situation. This returns a unit if the
     /// page mapping does not exist. This is a page fault.
     /// If the page does not exist then that case is a page fault. This function would return [None] to imply a page
@@ -298,13 +713,42 @@ impl Memory {
     /// // Unmapped page. This is a page fault situation.
     /// assert!(matches!(memory.translate_virtual(0b000_00000000_00000000_00000000_00000000_00000000_00000000__00000_00001010), None));
     /// ```
+    ///
+    /// The page size is taken from [Memory::page_item_bits].
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    ///
+    /// // 16 byte pages.
+    /// let mut memory = Memory::from(Vec::new());
+    /// memory.page_item_bits = 4;
+    /// memory.pages.insert(1, 3);
+    ///
+    /// assert_eq!(memory.translate_virtual(0x15), Some(0x35));
+    /// assert_eq!(memory.translate_virtual(0x25), None);
+    /// ```
     pub fn translate_virtual(&self, r#virtual: u64) -> Option<u64> {
-        let virtual_page = r#virtual.extract_page();
+        self.translate(Virtual(r#virtual)).map(u64::from)
+    }
+
+    /// Same as [Memory::translate_virtual] but the address space of the argument and result are checked by the type
+    /// system.
+    /// ```
+    /// use atln_processor::emulator::memory::{Memory, Physical, Virtual};
+    ///
+    /// let mut memory = Memory::from(Vec::new());
+    /// memory.page_item_bits = 4;
+    /// memory.pages.insert(1, 3);
+    ///
+    /// assert_eq!(memory.translate(Virtual(0x15)), Some(Physical(0x35)));
+    /// assert_eq!(memory.translate(0x25.into()), None);
+    /// ```
+    pub fn translate(&self, r#virtual: Virtual) -> Option<Physical> {
+        let virtual_page = r#virtual.extract_page_sized(self.page_item_bits);
         // Find the mapping based on the virtual page.
-        let physical_page = self.pages.get(&virtual_page)?.offset_page();
-        let virtual_item = r#virtual.extract_item();
+        let physical_page = self.pages.get(&virtual_page)?.offset_page_sized(self.page_item_bits);
+        let virtual_item = r#virtual.extract_item_sized(self.page_item_bits);
 
-        Some(physical_page.set_item(virtual_item))
+        Some(Physical(physical_page.set_item_sized(virtual_item, self.page_item_bits)))
     }
 
     /// Utility function to check for errors in an address frame when performing operations on memory and to handle
@@ -313,7 +757,7 @@ impl Memory {
     /// If the frame is marked as virtual through the [r#virtual] parameter, then the frame will have its address
     /// translated. This also tests for the following errors:
     /// - If the address is unaligned, then [Err(GetError::UnalignedFrame)] is returned.
-    /// - Otherwise, if a page fault occurred, then [Err(GetError::PageFault)] is returned.
+    /// - Otherwise, if a page fault occurred, then [Err(GetError::PageFault)] is returned with the virtual address.
     /// - Finally, if the address is out of bounds, then [Err(GetError::OutOfBounds)] is returned.
     /// ```
     /// assert!(false); // TODO: Test
@@ -325,16 +769,115 @@ impl Memory {
         if translate {
             frame.address = match self.translate_virtual(frame.address) {
                 Some(value) => value,
-                None => return Err(GetError::PageFault)
+                None => return Err(GetError::PageFault { address: frame.address })
             };
         }
 
+        // Devices are not part of the memory bytes and have their own ranges.
+        if self.frame_device(frame)?.is_some() { return Ok(()) }
+
         // Make sure the frame bounds lies in the memory size range.
-        if let Some(max_address) = self.max_address { if frame.max_address() > max_address { return Err(GetError::OutOfBounds) }}
+        // With a size limit, frames that overflow the address space are always out of bounds.
+        if let Some(max_address) = self.max_address {
+            match frame.max_address() {
+                Some(frame_max_address) => if frame_max_address > max_address { return Err(GetError::OutOfBounds) },
+                None => return Err(GetError::OutOfBounds)
+            }
+        }
 
         Ok(())
     }
 
+    /// Get the index of the attached device whose range contains the whole physical frame. [None] is returned if no
+    /// device shares an address with the frame. A frame that is only partly inside a device's range gives
+    /// [Err(GetError::OutOfBounds)].
+    fn frame_device(&self, frame: &Frame) -> Result<Option<usize>, GetError> {
+        let last = frame.address.saturating_add(frame.size.size() as u64 - 1);
+
+        for (index, device) in self.devices.iter().enumerate() {
+            // The end of a device range never overflows because it is checked when the device is attached.
+            if frame.address >= device.start + device.length || last < device.start { continue }
+            if device.contains(frame.address) && device.contains(last) { return Ok(Some(index)) }
+            return Err(GetError::OutOfBounds);
+        }
+
+        Ok(None)
+    }
+
+    /// Attach a device to a range of physical addresses. Frames which lie in the range are read from and written to the
+    /// device, and frames which are only partly in the range are out of bounds. Devices are not limited by
+    /// [Memory::max_address] or the size of the memory bytes.
+    /// - If the range is empty or reaches past the largest address, then [Err(AttachError::Range)] is returned.
+    /// - If the range overlaps another device, then [Err(AttachError::Overlapping)] is returned.
+    /// ```
+    /// use atln_processor::emulator::memory::{AttachError, Frame, GetError, Memory, MemoryMappedDevice};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// /// Counts the number of times it was read. Writing sets the count.
+    /// #[derive(Debug, Clone)]
+    /// struct Counter(u64);
+    ///
+    /// impl MemoryMappedDevice for Counter {
+    ///     fn read(&mut self, _offset: u64, _size: Size) -> Data {
+    ///         self.0 += 1;
+    ///         Data::Quad(self.0)
+    ///     }
+    ///
+    ///     fn write(&mut self, _offset: u64, _size: Size, value: Data) {
+    ///         self.0 = value.quad();
+    ///     }
+    ///
+    ///     fn clone_box(&self) -> Box<dyn MemoryMappedDevice> {
+    ///         Box::new(self.clone())
+    ///     }
+    /// }
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 8]);
+    /// memory.attach_device(0x1000, 8, Box::new(Counter(0))).unwrap();
+    ///
+    /// // Ranges cannot overlap.
+    /// assert_eq!(memory.attach_device(0x1004, 8, Box::new(Counter(0))), Err(AttachError::Overlapping));
+    /// assert_eq!(memory.attach_device(0x2000, 0, Box::new(Counter(0))), Err(AttachError::Range));
+    ///
+    /// let frame = Frame { address: 0x1000, size: Size::Quad };
+    /// assert_eq!(memory.get(frame.clone(), false).unwrap(), Data::Quad(1));
+    /// assert_eq!(memory.get(frame.clone(), false).unwrap(), Data::Quad(2));
+    ///
+    /// memory.set(frame.clone(), false, Data::Quad(100)).unwrap();
+    /// assert_eq!(memory.get(frame.clone(), false).unwrap(), Data::Quad(101));
+    ///
+    /// // Clones of the memory have their own copy of the device.
+    /// let mut copy = memory.clone();
+    /// assert_eq!(copy.get(frame.clone(), false).unwrap(), Data::Quad(102));
+    /// assert_eq!(memory.get(frame, false).unwrap(), Data::Quad(102));
+    ///
+    /// // The memory bytes were not touched.
+    /// assert_eq!(memory.bytes, vec![0u8; 8]);
+    ///
+    /// // A frame that starts in the memory bytes and ends inside a device is rejected rather than split.
+    /// let mut memory = Memory::from(vec![0u8; 16]);
+    /// memory.attach_device(0xC, 4, Box::new(Counter(0))).unwrap();
+    ///
+    /// let frame = Frame { address: 8, size: Size::Quad };
+    /// assert_eq!(memory.get(frame.clone(), false), Err(GetError::OutOfBounds));
+    /// assert_eq!(memory.set(frame, false, Data::Quad(1)), Err(GetError::OutOfBounds));
+    /// assert_eq!(memory.get(Frame { address: 8, size: Size::Dual }, false), Ok(Data::Dual(0)));
+    /// assert_eq!(memory.get(Frame { address: 0xC, size: Size::Dual }, false), Ok(Data::Quad(1)));
+    /// ```
+    pub fn attach_device(&mut self, start: u64, length: u64, device: Box<dyn MemoryMappedDevice>) -> Result<(), AttachError> {
+        let end = match start.checked_add(length) {
+            Some(end) if length > 0 => end,
+            _ => return Err(AttachError::Range)
+        };
+
+        if self.devices.iter().any(|other| start < other.start + other.length && other.start < end) {
+            return Err(AttachError::Overlapping);
+        }
+
+        self.devices.push(MappedDevice { start, length, device });
+        Ok(())
+    }
+
     /// Read and return the data targeted by the frame with safeguards and emulated hardware limitations. If the page
     /// is not cached in this list, then a [GetError::PageFault] is caused.
     /// ```
@@ -381,32 +924,690 @@ impl Memory {
     /// assert_eq!(memory.get(Frame { address: 4, size: Size::Word }, true).unwrap(), Data::Word(256));
     /// // endregion
     /// ```
+    ///
+    /// Every size has the same bounds check against the memory bytes, even without a [Memory::max_address].
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![1u8; 6]);
+    /// memory.max_address = None;
+    ///
+    /// assert_eq!(memory.get(Frame { address: 5, size: Size::Byte }, false), Ok(Data::Byte(1)));
+    /// assert_eq!(memory.get(Frame { address: 6, size: Size::Byte }, false), Err(GetError::OutOfBounds));
+    ///
+    /// // Starts 2 bytes before the end.
+    /// assert_eq!(memory.get(Frame { address: 4, size: Size::Dual }, false), Err(GetError::OutOfBounds));
+    /// ```
+    ///
+    /// Unaligned frames can be read if [Memory::allow_unaligned] is set.
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![ 0, 1, 2, 3, 4, 5, 6, 7 ]);
+    /// let frame = Frame { address: 1, size: Size::Quad };
+    ///
+    /// assert_eq!(memory.get(frame.clone(), false), Err(GetError::UnalignedFrame));
+    ///
+    /// memory.allow_unaligned = true;
+    /// assert_eq!(memory.get(Frame { address: 3, size: Size::Word }, false), Ok(Data::Word(0x0403)));
+    /// assert_eq!(memory.get(frame, false), Err(GetError::OutOfBounds));
+    /// ```
     pub fn get(&mut self, mut frame: Frame, r#virtual: bool) -> Result<number::Data, GetError> {
+        if self.allow_unaligned && !frame.is_aligned() {
+            let mut buffer = [0u8; QUAD_SIZE];
+            let buffer = &mut buffer[0..frame.size.size() as usize];
+            self.get_bytes(frame.address, buffer, r#virtual)?;
+
+            // Unwrapping is safe because the buffer is the size of the frame, which is the size of a variant.
+            return Ok(number::Data::from_le_bytes(buffer).unwrap());
+        }
+
         self.process_test_frame(&mut frame, r#virtual)?;
-        let mut max_buffer = [0u8; QUAD_SIZE];
-
-        Ok(match frame.size {
-            Size::Byte => {
-                let buffer = &mut max_buffer[0..BYTE_SIZE];
-                if read_vec_into_buffer(&self.bytes, frame.address as usize, buffer) != buffer.len() { return Err(GetError::OutOfBounds) }
-                number::Data::Byte(buffer[0])
-            },
-            Size::Word => {
-                let buffer = &mut max_buffer[0..WORD_SIZE];
-                if read_vec_into_buffer(&self.bytes, frame.address as usize, buffer) != buffer.len() { return Err(GetError::OutOfBounds) }
-                number::Data::Word(u16::from_le_bytes([ buffer[0], buffer[1] ]))
-            },
-            Size::Dual => {
-                let buffer = &mut max_buffer[0..DUAL_SIZE];
-                if read_vec_into_buffer(&self.bytes, frame.address as usize, buffer) != buffer.len() { return Err(GetError::OutOfBounds) }
-                number::Data::Dual(u32::from_le_bytes([ buffer[0], buffer[1], buffer[2], buffer[3] ]))
-            },
-            Size::Quad => {
-                let buffer = &mut max_buffer[0..QUAD_SIZE];
-                if read_vec_into_buffer(&self.bytes, frame.address as usize, buffer) != buffer.len() { return Err(GetError::OutOfBounds) }
-                number::Data::Quad(u64::from_le_bytes([ buffer[0], buffer[1], buffer[2], buffer[3], buffer[4], buffer[5], buffer[6], buffer[7] ]))
+
+        if let Some(index) = self.frame_device(&frame)? {
+            let mapped = &mut self.devices[index];
+            return Ok(mapped.device.read(frame.address - mapped.start, frame.size));
+        }
+
+        let range = self.frame_range(&frame)?;
+        self.check_written(range.clone())?;
+
+        // Unwrapping is safe because the range is the size of the frame, which is the size of a variant.
+        Ok(number::Data::from_le_bytes(&self.bytes[range]).unwrap())
+    }
+
+    /// Write data to the location targeted by the frame with the same safeguards and emulated hardware limitations as
+    /// [Memory::get]. The data is truncated or zero extended to the size of the frame.
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 8]);
+    ///
+    /// memory.set(Frame { address: 4, size: Size::Dual }, false, Data::Word(0x0102)).unwrap();
+    /// assert_eq!(memory.bytes, vec![0, 0, 0, 0, 2, 1, 0, 0]);
+    ///
+    /// // Truncated to the frame size.
+    /// memory.set(Frame { address: 0, size: Size::Byte }, false, Data::Word(0x0102)).unwrap();
+    /// assert_eq!(memory.bytes[0], 2);
+    ///
+    /// assert_eq!(memory.set(Frame { address: 8, size: Size::Byte }, false, Data::Byte(0)), Err(GetError::OutOfBounds));
+    ///
+    /// // Unaligned frames are only accepted when allowed.
+    /// let frame = Frame { address: 3, size: Size::Quad };
+    /// assert_eq!(memory.set(frame.clone(), false, Data::Byte(1)), Err(GetError::UnalignedFrame));
+    ///
+    /// memory.allow_unaligned = true;
+    /// assert_eq!(memory.set(frame, false, Data::Byte(1)), Err(GetError::OutOfBounds));
+    /// memory.set(Frame { address: 3, size: Size::Dual }, false, Data::Dual(0x0A0B0C0D)).unwrap();
+    /// assert_eq!(memory.bytes, vec![2, 0, 0, 0x0D, 0x0C, 0x0B, 0x0A, 0]);
+    ///
+    /// // Read only memory can still be read.
+    /// memory.read_only = true;
+    /// let frame = Frame { address: 0, size: Size::Byte };
+    /// assert_eq!(memory.set(frame.clone(), false, Data::Byte(9)), Err(GetError::ReadOnly));
+    /// assert_eq!(memory.get(frame, false), Ok(Data::Byte(2)));
+    /// ```
+    pub fn set(&mut self, mut frame: Frame, r#virtual: bool, data: number::Data) -> Result<(), GetError> {
+        if self.read_only { return Err(GetError::ReadOnly) }
+
+        if self.allow_unaligned && !frame.is_aligned() {
+            let size = frame.size.size() as usize;
+            return self.set_bytes(frame.address, &data.quad().to_le_bytes()[0..size], r#virtual);
+        }
+
+        self.process_test_frame(&mut frame, r#virtual)?;
+
+        if let Some(index) = self.frame_device(&frame)? {
+            let mapped = &mut self.devices[index];
+            mapped.device.write(frame.address - mapped.start, frame.size, data);
+            return Ok(());
+        }
+
+        let range = self.frame_range(&frame)?;
+        let size = range.len();
+
+        self.bytes[range.clone()].copy_from_slice(&data.quad().to_le_bytes()[0..size]);
+        self.mark_written(range);
+        Ok(())
+    }
+
+    /// Same as [Memory::set] but the data is not truncated. If the value has significant bits past the size of the
+    /// frame then [Err(GetError::Truncated)] is returned and nothing is written.
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 8]);
+    /// let frame = Frame { address: 0, size: Size::Byte };
+    ///
+    /// // The variant may be wider than the frame as long as the value fits.
+    /// memory.set_checked(frame.clone(), false, Data::Quad(0xFF)).unwrap();
+    /// assert_eq!(memory.bytes[0], 0xFF);
+    ///
+    /// assert_eq!(memory.set_checked(frame, false, Data::Quad(0x100)), Err(GetError::Truncated));
+    /// assert_eq!(memory.bytes[0], 0xFF);
+    /// ```
+    pub fn set_checked(&mut self, frame: Frame, r#virtual: bool, data: number::Data) -> Result<(), GetError> {
+        if data.quad().checked_shr(frame.size.size() as u32 * 8).unwrap_or(0) != 0 { return Err(GetError::Truncated) }
+        self.set(frame, r#virtual, data)
+    }
+
+    /// Get the memory bytes targeted by a frame so they can be edited in place. The frame is checked and translated the
+    /// same way as with [Memory::set]. The bytes of a frame must be in one page, and frames that target an attached
+    /// device have no bytes, [Err(GetError::OutOfBounds)] is returned for both. If the page holding the last byte is
+    /// not mapped then [Err(GetError::PageFault)] is returned. Read only memory hands out no bytes and returns
+    /// [Err(GetError::ReadOnly)].
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 32]);
+    /// memory.page_item_bits = 4;
+    /// memory.pages.insert(0, 1);
+    ///
+    /// let frame = Frame { address: 4, size: Size::Dual };
+    /// memory.slice_mut(frame.clone(), true).unwrap().copy_from_slice(&[ 1, 2, 3, 4 ]);
+    ///
+    /// assert_eq!(memory.get(frame, true).unwrap(), Data::Dual(0x04030201));
+    /// assert_eq!(memory.bytes[20..24], [ 1, 2, 3, 4 ]);
+    ///
+    /// // The second virtual page is not mapped.
+    /// let frame = Frame { address: 0x14, size: Size::Dual };
+    /// assert_eq!(memory.slice_mut(frame.clone(), true), Err(GetError::PageFault { address: 0x14 }));
+    /// assert_eq!(memory.get(frame, true), Err(GetError::PageFault { address: 0x14 }));
+    ///
+    /// // With 4 byte pages a quad spans two pages, and the page holding its last byte is not mapped.
+    /// memory.page_item_bits = 2;
+    /// let frame = Frame { address: 0, size: Size::Quad };
+    /// assert_eq!(memory.slice_mut(frame, true), Err(GetError::PageFault { address: 7 }));
+    /// ```
+    pub fn slice_mut(&mut self, mut frame: Frame, r#virtual: bool) -> Result<&mut [u8], GetError> {
+        if self.read_only { return Err(GetError::ReadOnly) }
+
+        let size = frame.size.size() as u64;
+
+        // Check the last byte lands where the rest of the frame is. This is only false if pages are smaller than frames.
+        let last_address = frame.address.wrapping_add(size - 1);
+        let last = if r#virtual { Some(self.translate_virtual(last_address)) } else { None };
+        self.process_test_frame(&mut frame, r#virtual)?;
+
+        match last {
+            Some(None) => return Err(GetError::PageFault { address: last_address }),
+            Some(Some(last)) if last != frame.address.wrapping_add(size - 1) => return Err(GetError::OutOfBounds),
+            _ => {}
+        }
+
+        if self.frame_device(&frame)?.is_some() { return Err(GetError::OutOfBounds) }
+
+        let range = self.frame_range(&frame)?;
+
+        // The bytes are handed out to be written, so they count as initialized.
+        self.mark_written(range.clone());
+        Ok(&mut self.bytes[range])
+    }
+
+    /// Set every byte in a range of memory to the same value. Virtual ranges are split where they cross into a new
+    /// page so each page is translated separately. The whole range is checked before anything is written, so on error
+    /// memory is left unchanged. This only initializes memory bytes, attached devices are not written to.
+    /// - If the memory is read only, then [Err(GetError::ReadOnly)] is returned.
+    /// - If a virtual page in the range is not mapped, then [Err(GetError::PageFault)] is returned.
+    /// - If any part of the range is outside the memory, then [Err(GetError::OutOfBounds)] is returned.
+    /// ```
+    /// use atln_processor::emulator::memory::{GetError, Memory};
+    ///
+    /// // 16 byte pages where the second virtual page comes before the first in physical memory.
+    /// let mut memory = Memory::from(vec![0u8; 48]);
+    /// memory.page_item_bits = 4;
+    /// memory.pages.insert(0, 2);
+    /// memory.pages.insert(1, 0);
+    ///
+    /// memory.fill(0x0C, 8, 0xAA, true).unwrap();
+    /// assert_eq!(memory.bytes[0x2C..0x30], [0xAA; 4]);
+    /// assert_eq!(memory.bytes[0x00..0x04], [0xAA; 4]);
+    /// assert_eq!(memory.bytes.iter().filter(|byte| **byte == 0xAA).count(), 8);
+    ///
+    /// // The third virtual page is not mapped, nothing is written.
+    /// assert_eq!(memory.fill(0x1C, 8, 0xBB, true), Err(GetError::PageFault { address: 0x20 }));
+    /// assert!(!memory.bytes.contains(&0xBB));
+    ///
+    /// assert_eq!(memory.fill(40, 10, 0xBB, false), Err(GetError::OutOfBounds));
+    /// memory.fill(40, 8, 0xBB, false).unwrap();
+    /// assert_eq!(memory.bytes[40..48], [0xBB; 8]);
+    ///
+    /// // Empty ranges succeed anywhere because they touch nothing.
+    /// assert_eq!(memory.fill(0x1C, 0, 0xCC, true), Ok(()));
+    /// assert_eq!(memory.fill(u64::MAX, 0, 0xCC, false), Ok(()));
+    /// ```
+    pub fn fill(&mut self, address: u64, length: u64, value: u8, r#virtual: bool) -> Result<(), GetError> {
+        if self.read_only { return Err(GetError::ReadOnly) }
+
+        for range in self.physical_ranges(address, length, r#virtual)? {
+            self.bytes[range.clone()].fill(value);
+            self.mark_written(range);
+        }
+
+        Ok(())
+    }
+
+    /// Read bytes starting at an address into the buffer. Reads need no alignment and virtual reads may cross pages.
+    /// Like [Memory::fill], attached devices are not read from.
+    /// - If a virtual page in the range is not mapped, then [Err(GetError::PageFault)] is returned.
+    /// - If any part of the range is outside the memory, then [Err(GetError::OutOfBounds)] is returned.
+    /// ```
+    /// use atln_processor::emulator::memory::{GetError, Memory};
+    ///
+    /// let mut memory = Memory::from(vec![ 1, 2, 3, 4 ]);
+    /// let mut buffer = [0u8; 3];
+    ///
+    /// memory.get_bytes(1, &mut buffer, false).unwrap();
+    /// assert_eq!(buffer, [2, 3, 4]);
+    /// assert_eq!(memory.get_bytes(2, &mut buffer, false), Err(GetError::OutOfBounds));
+    ///
+    /// // Nothing is read, so an unmapped address is not a page fault.
+    /// assert_eq!(memory.get_bytes(0x4000, &mut [], true), Ok(()));
+    /// ```
+    pub fn get_bytes(&self, address: u64, buffer: &mut [u8], r#virtual: bool) -> Result<(), GetError> {
+        let mut start = 0;
+        let ranges = self.physical_ranges(address, buffer.len() as u64, r#virtual)?;
+        for range in &ranges { self.check_written(range.clone())? }
+
+        for range in ranges {
+            let end = start + range.len();
+            buffer[start..end].copy_from_slice(&self.bytes[range]);
+            start = end;
+        }
+
+        Ok(())
+    }
+
+    /// Write bytes starting at an address. The whole range is checked before anything is written, so on error memory
+    /// is left unchanged. Like [Memory::fill], attached devices are not written to.
+    /// - If the memory is read only, then [Err(GetError::ReadOnly)] is returned.
+    /// - If a virtual page in the range is not mapped, then [Err(GetError::PageFault)] is returned.
+    /// - If any part of the range is outside the memory, then [Err(GetError::OutOfBounds)] is returned.
+    /// ```
+    /// use atln_processor::emulator::memory::{GetError, Memory};
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 4]);
+    ///
+    /// memory.set_bytes(1, &[ 5, 6 ], false).unwrap();
+    /// assert_eq!(memory.bytes, vec![ 0, 5, 6, 0 ]);
+    /// assert_eq!(memory.set_bytes(3, &[ 7, 8 ], false), Err(GetError::OutOfBounds));
+    /// assert_eq!(memory.bytes, vec![ 0, 5, 6, 0 ]);
+    ///
+    /// // Nothing is written, so an unmapped address is not a page fault.
+    /// assert_eq!(memory.set_bytes(0x4000, &[], true), Ok(()));
+    ///
+    /// memory.read_only = true;
+    /// assert_eq!(memory.set_bytes(0, &[ 9 ], false), Err(GetError::ReadOnly));
+    /// assert_eq!(memory.bytes, vec![ 0, 5, 6, 0 ]);
+    /// ```
+    pub fn set_bytes(&mut self, address: u64, bytes: &[u8], r#virtual: bool) -> Result<(), GetError> {
+        if self.read_only { return Err(GetError::ReadOnly) }
+
+        let mut start = 0;
+
+        for range in self.physical_ranges(address, bytes.len() as u64, r#virtual)? {
+            let end = start + range.len();
+            self.bytes[range.clone()].copy_from_slice(&bytes[start..end]);
+            self.mark_written(range);
+            start = end;
+        }
+
+        Ok(())
+    }
+
+    /// Get the range of memory bytes a physical frame covers. Every frame access to the memory bytes goes through this,
+    /// so all sizes have the same bounds check. If any byte of the frame is past the end of the memory bytes then
+    /// [Err(GetError::OutOfBounds)] is returned, even if [Memory::max_address] allows it.
+    fn frame_range(&self, frame: &Frame) -> Result<Range<usize>, GetError> {
+        let start = frame.address as usize;
+
+        match start.checked_add(frame.size.size() as usize) {
+            Some(end) if frame.address <= usize::MAX as u64 && end <= self.bytes.len() => Ok(start..end),
+            _ => Err(GetError::OutOfBounds)
+        }
+    }
+
+    /// Turn on poison mode. From then on reading a memory byte that has not been written to since poison mode was turned
+    /// on gives [Err(GetError::Uninitialized)]. This is a debugging aid for catching reads of uninitialized memory, and
+    /// attached devices are not tracked.
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 8]);
+    /// memory.enable_poison();
+    ///
+    /// assert_eq!(memory.get(Frame { address: 0, size: Size::Byte }, false), Err(GetError::Uninitialized));
+    ///
+    /// memory.set(Frame { address: 0, size: Size::Byte }, false, Data::Byte(5)).unwrap();
+    /// assert_eq!(memory.get(Frame { address: 0, size: Size::Byte }, false), Ok(Data::Byte(5)));
+    ///
+    /// // Every byte of the frame must have been written.
+    /// assert_eq!(memory.get(Frame { address: 0, size: Size::Word }, false), Err(GetError::Uninitialized));
+    /// ```
+    pub fn enable_poison(&mut self) {
+        self.written = Some(vec![false; self.bytes.len()]);
+    }
+
+    /// Record that the memory bytes in the physical range were written to if poison mode is on.
+    fn mark_written(&mut self, range: Range<usize>) {
+        if let Some(written) = &mut self.written {
+            if written.len() < range.end { written.resize(range.end, false) }
+            written[range].fill(true);
+        }
+    }
+
+    /// Check that all the memory bytes in the physical range were written to if poison mode is on.
+    fn check_written(&self, range: Range<usize>) -> Result<(), GetError> {
+        match &self.written {
+            Some(written) if range.into_iter().any(|index| !written.get(index).copied().unwrap_or(false)) => Err(GetError::Uninitialized),
+            _ => Ok(())
+        }
+    }
+
+    /// Translate and bounds check a range of addresses, splitting it where it crosses into a new page. The physical
+    /// ranges are returned in the order of the addresses they were translated from. An empty range touches nothing, so
+    /// it is never translated or bounds checked.
+    fn physical_ranges(&self, address: u64, length: u64, r#virtual: bool) -> Result<Vec<Range<usize>>, GetError> {
+        if length == 0 { return Ok(Vec::new()) }
+        if address.checked_add(length).is_none() { return Err(GetError::OutOfBounds) }
+
+        let item_mask = page_item_mask(self.page_item_bits);
+        let limit = match self.max_address {
+            Some(max_address) => max_address.min(self.bytes.len() as u64),
+            None => self.bytes.len() as u64
+        };
+
+        // Translate and check every page the range touches.
+        let mut ranges = Vec::new();
+        let mut current = address;
+        let end = address + length;
+
+        while current < end {
+            let page_remaining = (item_mask - (current & item_mask)).saturating_add(1);
+            let chunk_length = page_remaining.min(end - current);
+
+            let physical = if r#virtual {
+                match self.translate_virtual(current) {
+                    Some(value) => value,
+                    None => return Err(GetError::PageFault { address: current })
+                }
+            } else { current };
+
+            match physical.checked_add(chunk_length) {
+                Some(physical_end) if physical_end <= limit => ranges.push(physical as usize..physical_end as usize),
+                _ => return Err(GetError::OutOfBounds)
             }
-        })
+
+            current += chunk_length;
+        }
+
+        Ok(ranges)
+    }
+
+    /// Add to the data targeted by the frame as a single read-modify-write step and return the data from before the
+    /// addition. The sum wraps around at the frame size. This is the building block for atomic operations used by
+    /// synchronised instructions, mutual exclusion between processors is left to the caller.
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![ 10, 0, 255, 0 ]);
+    ///
+    /// assert_eq!(memory.fetch_add(Frame { address: 0, size: Size::Word }, false, Data::Byte(5)).unwrap(), Data::Word(10));
+    /// assert_eq!(memory.get(Frame { address: 0, size: Size::Word }, false).unwrap(), Data::Word(15));
+    ///
+    /// // Wraps at the frame size.
+    /// assert_eq!(memory.fetch_add(Frame { address: 2, size: Size::Byte }, false, Data::Byte(1)).unwrap(), Data::Byte(255));
+    /// assert_eq!(memory.bytes, vec![ 15, 0, 0, 0 ]);
+    /// ```
+    pub fn fetch_add(&mut self, frame: Frame, r#virtual: bool, delta: number::Data) -> Result<number::Data, GetError> {
+        let previous = self.get(frame.clone(), r#virtual)?;
+        self.set(frame, r#virtual, previous.wrapping_add(&delta))?;
+        Ok(previous)
+    }
+
+    /// Capture the bytes, page mappings, size limit, poison tracking and access settings of this memory so it can later
+    /// be returned to this exact state with [Memory::restore].
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![ 1, 2, 3, 4 ]);
+    /// memory.pages.insert(0, 0);
+    ///
+    /// let snapshot = memory.snapshot();
+    ///
+    /// // Run forward by changing memory and its page table.
+    /// memory.bytes[0] = 100;
+    /// memory.pages.insert(1, 0);
+    /// assert_ne!(memory.snapshot(), snapshot);
+    ///
+    /// memory.restore(&snapshot);
+    /// assert_eq!(memory.snapshot(), snapshot);
+    /// assert_eq!(memory.get(Frame { address: 0, size: Size::Byte }, false).unwrap(), Data::Byte(1));
+    /// ```
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot {
+            bytes: self.bytes.clone(),
+            max_address: self.max_address,
+            page_item_bits: self.page_item_bits,
+            pages: self.pages.clone(),
+            written: self.written.clone(),
+            allow_unaligned: self.allow_unaligned,
+            read_only: self.read_only
+        }
+    }
+
+    /// Return the memory to the state captured in a [MemorySnapshot]. Attached devices are left as they are.
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 8]);
+    /// memory.enable_poison();
+    /// memory.set(Frame { address: 0, size: Size::Byte }, false, Data::Byte(5)).unwrap();
+    ///
+    /// let snapshot = memory.snapshot();
+    ///
+    /// // Writing initializes more bytes, and the settings change.
+    /// memory.set(Frame { address: 1, size: Size::Byte }, false, Data::Byte(6)).unwrap();
+    /// memory.page_item_bits = 2;
+    /// memory.allow_unaligned = true;
+    /// memory.read_only = true;
+    ///
+    /// memory.restore(&snapshot);
+    /// assert_eq!(memory.snapshot(), snapshot);
+    /// assert_eq!(memory.get(Frame { address: 0, size: Size::Byte }, false), Ok(Data::Byte(5)));
+    /// assert_eq!(memory.get(Frame { address: 1, size: Size::Byte }, false), Err(GetError::Uninitialized));
+    /// ```
+    pub fn restore(&mut self, snapshot: &MemorySnapshot) {
+        self.bytes.clone_from(&snapshot.bytes);
+        self.max_address = snapshot.max_address;
+        self.page_item_bits = snapshot.page_item_bits;
+        self.pages.clone_from(&snapshot.pages);
+        self.written.clone_from(&snapshot.written);
+        self.allow_unaligned = snapshot.allow_unaligned;
+        self.read_only = snapshot.read_only;
+    }
+
+    /// Create a cursor for writing data one after another starting at an address. Use this instead of repeated calls
+    /// to [Memory::set] when loading programs.
+    pub fn writer(&mut self, start: u64, r#virtual: bool) -> MemoryWriter<'_> {
+        MemoryWriter {
+            memory: self,
+            position: start,
+            r#virtual,
+            translation: None
+        }
+    }
+
+    /// Create a view of a region of physical memory where address 0 of the view is the base address. Accesses that
+    /// reach past the length of the window are out of bounds, which confines a program to the region without setting
+    /// up virtual memory.
+    pub fn window(&mut self, base: u64, length: u64) -> MemoryWindow<'_> {
+        MemoryWindow {
+            memory: self,
+            base,
+            length
+        }
+    }
+}
+
+/// A bounds limited view of a region of physical memory. Created with [Memory::window].
+/// ```
+/// use atln_processor::emulator::memory::{Frame, GetError, Memory};
+/// use atln_processor::number::{Data, Size};
+///
+/// let mut memory = Memory::from(vec![0u8; 32]);
+/// let mut window = memory.window(8, 16);
+///
+/// window.set(Frame { address: 0, size: Size::Quad }, Data::Quad(0x0807060504030201)).unwrap();
+/// assert_eq!(window.get(Frame { address: 0, size: Size::Byte }), Ok(Data::Byte(1)));
+///
+/// // Accesses past the window fault even though the memory is larger.
+/// assert_eq!(window.get(Frame { address: 16, size: Size::Byte }), Err(GetError::OutOfBounds));
+/// assert_eq!(window.set(Frame { address: 12, size: Size::Quad }, Data::Quad(0)), Err(GetError::OutOfBounds));
+///
+/// assert_eq!(memory.bytes[8..16], [1, 2, 3, 4, 5, 6, 7, 8]);
+/// ```
+#[derive(Debug)]
+pub struct MemoryWindow<'a> {
+    memory: &'a mut Memory,
+    base: u64,
+    length: u64
+}
+
+impl MemoryWindow<'_> {
+    /// Move the frame from window addresses to memory addresses. If the frame does not fit in the window then
+    /// [Err(GetError::OutOfBounds)] is returned.
+    fn translate(&self, frame: Frame) -> Result<Frame, GetError> {
+        match frame.max_address() {
+            Some(max_address) if max_address <= self.length => (),
+            _ => return Err(GetError::OutOfBounds)
+        }
+
+        match self.base.checked_add(frame.address) {
+            Some(address) => Ok(Frame { address, size: frame.size }),
+            None => Err(GetError::OutOfBounds)
+        }
+    }
+
+    /// Same as [Memory::get] with a physical address relative to the start of the window.
+    pub fn get(&mut self, frame: Frame) -> Result<number::Data, GetError> {
+        let frame = self.translate(frame)?;
+        self.memory.get(frame, false)
+    }
+
+    /// Same as [Memory::set] with a physical address relative to the start of the window.
+    pub fn set(&mut self, frame: Frame, data: number::Data) -> Result<(), GetError> {
+        let frame = self.translate(frame)?;
+        self.memory.set(frame, false, data)
+    }
+}
+
+/// A cursor which writes data sequentially into memory. The physical page of the last virtual page written to is
+/// remembered, so the page table is only consulted again once the cursor crosses into another page. Data is written
+/// as raw little endian bytes, so unlike [Memory::set] the writes do not need to be aligned. Like [Memory::fill] this
+/// only writes memory bytes and does not write to attached devices.
+/// ```
+/// use atln_processor::emulator::memory::{GetError, Memory};
+/// use atln_processor::number::Data;
+///
+/// // 16 byte pages where the second virtual page comes before the first in physical memory.
+/// let mut memory = Memory::from(vec![0u8; 32]);
+/// memory.page_item_bits = 4;
+/// memory.pages.insert(0, 1);
+/// memory.pages.insert(1, 0);
+///
+/// let mut writer = memory.writer(0x0E, true);
+/// writer.write_number(Data::Dual(0x44332211)).unwrap();
+/// writer.write_number(Data::Byte(0x55)).unwrap();
+/// assert_eq!(writer.position(), 0x13);
+///
+/// // The third virtual page is not mapped.
+/// let mut writer = memory.writer(0x1F, true);
+/// assert_eq!(writer.write_number(Data::Word(0xFFFF)), Err(GetError::PageFault { address: 0x20 }));
+/// assert_eq!(writer.position(), 0x1F);
+///
+/// // The last 4 bytes would be past the end of memory.
+/// assert_eq!(memory.writer(0x1C, false).write_number(Data::Quad(0)), Err(GetError::OutOfBounds));
+///
+/// assert_eq!(memory.bytes[0x1E..0x20], [0x11, 0x22]);
+/// assert_eq!(memory.bytes[0x00..0x03], [0x33, 0x44, 0x55]);
+/// ```
+#[derive(Debug)]
+pub struct MemoryWriter<'a> {
+    memory: &'a mut Memory,
+    position: u64,
+    r#virtual: bool,
+    /// The last translated virtual page and the physical page it maps to.
+    translation: Option<(u64, u64)>
+}
+
+impl MemoryWriter<'_> {
+    /// The address the next write begins at.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Translate an address, reusing the last translation if the address is in the same page.
+    fn physical(&mut self, address: u64) -> Result<u64, GetError> {
+        if !self.r#virtual { return Ok(address) }
+
+        let item_bits = self.memory.page_item_bits;
+        let virtual_page = address.extract_page_sized(item_bits);
+
+        let physical_page = match self.translation {
+            Some((cached_page, physical_page)) if cached_page == virtual_page => physical_page,
+            _ => {
+                let physical_page = match self.memory.pages.get(&virtual_page) {
+                    Some(page) => page.offset_page_sized(item_bits),
+                    None => return Err(GetError::PageFault { address })
+                };
+
+                self.translation = Some((virtual_page, physical_page));
+                physical_page
+            }
+        };
+
+        Ok(physical_page.set_item_sized(address.extract_item_sized(item_bits), item_bits))
+    }
+
+    /// Write the data at the current position and advance past it. Every byte is checked before anything is written,
+    /// so on error memory and the position are left unchanged.
+    /// - If the memory is read only, then [Err(GetError::ReadOnly)] is returned.
+    /// - If a virtual page the data lands in is not mapped, then [Err(GetError::PageFault)] is returned.
+    /// - If any byte is outside the memory, then [Err(GetError::OutOfBounds)] is returned.
+    pub fn write_number(&mut self, data: number::Data) -> Result<(), GetError> {
+        if self.memory.read_only { return Err(GetError::ReadOnly) }
+
+        let size = data.size() as usize;
+        let bytes = data.quad().to_le_bytes();
+        let limit = match self.memory.max_address {
+            Some(max_address) => max_address.min(self.memory.bytes.len() as u64),
+            None => self.memory.bytes.len() as u64
+        };
+
+        // Data that stays in one page is contiguous in physical memory and only needs one translation. This is every
+        // write that is aligned to its size.
+        let item_mask = page_item_mask(self.memory.page_item_bits);
+        let same_page = match self.position.checked_add(size as u64 - 1) {
+            Some(last) => !self.r#virtual || self.position & !item_mask == last & !item_mask,
+            None => false
+        };
+
+        if same_page {
+            let start = self.physical(self.position)?;
+            let end = match start.checked_add(size as u64) {
+                Some(end) if end <= limit => end as usize,
+                _ => return Err(GetError::OutOfBounds)
+            };
+
+            self.memory.bytes[start as usize..end].copy_from_slice(&bytes[..size]);
+            self.memory.mark_written(start as usize..end);
+            self.position = self.position.wrapping_add(size as u64);
+            return Ok(());
+        }
+
+        let mut targets = [0usize; QUAD_SIZE];
+        for (index, target) in targets.iter_mut().enumerate().take(size) {
+            let address = match self.position.checked_add(index as u64) {
+                Some(address) => self.physical(address)?,
+                None => return Err(GetError::OutOfBounds)
+            };
+
+            if address >= limit { return Err(GetError::OutOfBounds) }
+            *target = address as usize;
+        }
+
+        for (target, byte) in targets.iter().zip(bytes.iter()).take(size) {
+            self.memory.bytes[*target] = *byte;
+            self.memory.mark_written(*target..*target + 1);
+        }
+
+        self.position = self.position.wrapping_add(size as u64);
+        Ok(())
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self {
+            bytes: Vec::new(),
+            max_address: None,
+            page_item_bits: PAGE_ITEM_BITS,
+            pages: HashMap::new(),
+            devices: Vec::new(),
+            written: None,
+            allow_unaligned: false,
+            read_only: false
+        }
     }
 }
 
@@ -415,9 +1616,13 @@ impl From<Vec<u8>> for Memory {
     fn from(value: Vec<u8>) -> Self {
         Self {
             max_address: Some(value.len() as u64),
-            page_size: 0,
+            page_item_bits: PAGE_ITEM_BITS,
             bytes: value,
-            pages: HashMap::new()
+            pages: HashMap::new(),
+            devices: Vec::new(),
+            written: None,
+            allow_unaligned: false,
+            read_only: false
         }
     }
 }
\ No newline at end of file
@@ -1,24 +1,928 @@
-use emulator::memory::Memory;
-use super::processor::instruction::Instruction;
+use std::collections::HashMap;
+use emulator::memory::{GetError, Memory, MemoryAccess};
+use number;
+use super::processor::instruction::{AccessSource, DestinationError, Instruction, InstructionConstructError};
+use super::processor::instruction::operand::{OperandRef, ResolvedOperand};
+use super::processor::instruction::operation::{Extension, OperationExecuteError};
 
 pub mod array;
 pub mod instruction;
 
+/// Number of times each operation was executed, see [Core::histogram].
+pub type OperationHistogram = HashMap<Extension, u64>;
+
 /// Ports list for input and output.
 pub type Ports = [u8; 8];
 
-pub struct Core {
+/// Number of general purpose registers. Register codes are 3 bits, so every code indexes one of these.
+pub const REGISTERS_COUNT: usize = 8;
+
+/// Assembly names of the general purpose registers, indexed by register code.
+pub const REGISTER_MNEMONICS: [&str; REGISTERS_COUNT] = [ "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7" ];
+
+/// Address execution begins at unless a core is configured otherwise.
+pub const RESET_VECTOR: u64 = 0;
+/// Stack pointer a core starts with unless configured otherwise.
+pub const INITIAL_STACK_POINTER: u64 = 0;
 
+pub const ZERO_FLAG    : u64 = 1 << 0;
+pub const CARRY_FLAG   : u64 = 1 << 1;
+pub const SIGN_FLAG    : u64 = 1 << 2;
+pub const OVERFLOW_FLAG: u64 = 1 << 3;
+
+/// A single processor which executes instructions with its own execution context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Core {
+    pub context: Context,
+    /// Context the core was created with. [Core::reset] returns to this context, other than the instruction pointer and
+    /// stack pointer which are taken from [Core::entry_point] and [Core::initial_stack_pointer].
+    pub initial_context: Context,
+    /// Address the instruction pointer is set to when the core is reset.
+    pub entry_point: u64,
+    /// Value the stack pointer is set to when the core is reset.
+    pub initial_stack_pointer: u64,
+    /// Whether instructions must start on an even address. Fetching from an odd address is an illegal fetch when this
+    /// is enabled.
+    pub require_aligned_ip: bool,
+    /// Whether the core executed a halt operation. [Core::run] stops once this is set and [Core::reset] clears it.
+    pub halted: bool,
+    /// What [Core::run] does when the instruction at the instruction pointer cannot be decoded.
+    pub on_decode_error: DecodePolicy,
+    /// Address execution continues at when an instruction cannot be decoded and [Core::on_decode_error] is
+    /// [DecodePolicy::Trap].
+    pub illegal_instruction_vector: u64,
+    /// Emulated cycles each executed instruction adds to [Core::cycles].
+    pub cost_model: CostModel,
+    cycles: u64,
+    histogram: Option<OperationHistogram>
 }
 
 /// The execution context.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Context {
+    pub registers: RegisterFile,
+    /// Whether addresses are translated through the memory pages. When this is false the processor is in real mode.
+    pub virtual_mode: bool
+}
+
+/// Builds a [Context] with an entry point and preset registers so a program can be loaded without mutating a core's
+/// context after it is constructed. The instruction pointer and stack pointer start at [RESET_VECTOR] and
+/// [INITIAL_STACK_POINTER], other unset values are the same as [Context::default].
+/// ```
+/// use atln_processor::emulator::processor::processor::{ContextBuilder, Core, RegisterError};
+///
+/// let context = ContextBuilder::default()
+///     .instruction_pointer(0x400)
+///     .register(7, 0xFF00).unwrap()
+///     .virtual_mode(true)
+///     .build();
+///
+/// let core = Core::with_context(context);
+///
+/// assert_eq!(core.context.registers.instruction_pointer, 0x400);
+/// assert_eq!(core.context.registers.general[7], 0xFF00);
+/// assert!(core.context.virtual_mode);
+///
+/// assert_eq!(ContextBuilder::default().register(8, 0).unwrap_err(), RegisterError::OutOfBounds);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContextBuilder {
+    context: Context
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        let mut context = Context::default();
+        context.registers.instruction_pointer = RESET_VECTOR;
+        context.registers.stack_pointer = INITIAL_STACK_POINTER;
+
+        Self { context }
+    }
+}
+
+impl ContextBuilder {
+    /// Set the address execution begins at.
+    pub fn instruction_pointer(mut self, instruction_pointer: u64) -> Self {
+        self.context.registers.instruction_pointer = instruction_pointer;
+        self
+    }
+
+    /// Set the address of the top of the stack.
+    pub fn stack_pointer(mut self, stack_pointer: u64) -> Self {
+        self.context.registers.stack_pointer = stack_pointer;
+        self
+    }
+
+    /// Set the address of the top of the stack and ensure it lies within memory. The stack pointer may equal the
+    /// memory size, which is an empty stack that grows down from the end of memory. If the memory has no size limit
+    /// then any address is accepted, otherwise [Err(RegisterError::StackPointer)] is returned for addresses past the
+    /// end.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    /// use atln_processor::emulator::processor::processor::{ContextBuilder, RegisterError};
+    ///
+    /// let memory = Memory::from(vec![0u8; 256]);
+    ///
+    /// assert!(ContextBuilder::default().stack_pointer_within(256, &memory).is_ok());
+    /// assert_eq!(ContextBuilder::default().stack_pointer_within(257, &memory).unwrap_err(), RegisterError::StackPointer);
+    /// assert!(ContextBuilder::default().stack_pointer_within(u64::MAX, &Memory::default()).is_ok());
+    /// ```
+    pub fn stack_pointer_within(self, stack_pointer: u64, memory: &Memory) -> Result<Self, RegisterError> {
+        if matches!(memory.max_address, Some(max_address) if stack_pointer > max_address) { return Err(RegisterError::StackPointer) }
+        Ok(self.stack_pointer(stack_pointer))
+    }
+
+    /// Preset a register. If the index does not correspond to a register then [Err(RegisterError::OutOfBounds)] is
+    /// returned.
+    pub fn register(mut self, index: u8, value: u64) -> Result<Self, RegisterError> {
+        self.context.set_register(index, value)?;
+        Ok(self)
+    }
+
+    /// Set whether execution begins in virtual mode rather than real mode.
+    pub fn virtual_mode(mut self, virtual_mode: bool) -> Self {
+        self.context.virtual_mode = virtual_mode;
+        self
+    }
+
+    pub fn build(self) -> Context {
+        self.context
+    }
+}
+
+/// Caused by accessing a register that does not exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterError {
+    /// The register index is larger than the number of registers.
+    OutOfBounds,
+    /// The stack pointer is outside of memory.
+    StackPointer
+}
 
+#[derive(Debug)]
+pub enum FetchError {
+    /// Alignment is required and the instruction pointer is not on an even address.
+    UnalignedInstructionPointer,
+    /// Failed to decode the instruction at the instruction pointer.
+    Decode(InstructionConstructError)
+}
+
+/// What [Core::run] does when the instruction at the instruction pointer cannot be decoded.
+/// ```
+/// use atln_processor::emulator::memory::Memory;
+/// use atln_processor::emulator::processor::processor::{Core, DecodePolicy, FetchError, StepError};
+/// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+/// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+/// use atln_processor::emulator::processor::processor::instruction::operation::{DATA_CODE, Extension};
+/// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+/// use atln_processor::emulator::processor::processor::instruction::operation::control::Control;
+/// use atln_processor::number;
+///
+/// let add = Instruction {
+///     extension: Extension::Arithmetic(Arithmetic::Add),
+///     data: Some(Data {
+///         width: number::Size::Quad,
+///         destination: Destination::Static,
+///         synchronous: false,
+///         operands: Operands::all(1, Dynamic::Constant(number::Data::Byte(5)))
+///     })
+/// };
+///
+/// // The data extension has no operations, so the instruction after the addition is invalid. A halt follows it for
+/// // the trap to go to.
+/// let invalid = add.encode().len() as u64;
+/// let halt = Instruction { extension: Extension::Control(Control::Halt), data: None };
+/// let code = [add.encode(), vec![ DATA_CODE << 2, 0 ], halt.encode()].concat();
+///
+/// let run = |policy: DecodePolicy| {
+///     let mut core = Core::default();
+///     core.on_decode_error = policy;
+///     core.illegal_instruction_vector = invalid + 2;
+///
+///     let result = core.run(&mut Memory::from(code.clone()));
+///     assert_eq!(core.context.registers.general[1], 5);
+///     (core, result)
+/// };
+///
+/// let (core, result) = run(DecodePolicy::default());
+/// assert!(matches!(result, Err(StepError::Fetch(FetchError::Decode(_)))));
+/// assert!(!core.halted);
+///
+/// // Halting leaves the instruction pointer at the invalid instruction.
+/// let (core, result) = run(DecodePolicy::Halt);
+/// assert!(result.is_ok());
+/// assert!(core.halted);
+/// assert_eq!(core.context.registers.instruction_pointer, invalid);
+///
+/// // Trapping continues at the vector, which halts.
+/// let (core, result) = run(DecodePolicy::Trap);
+/// assert!(result.is_ok());
+/// assert!(core.halted);
+/// assert_eq!(core.context.registers.instruction_pointer, code.len() as u64);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodePolicy {
+    /// Continue at [Core::illegal_instruction_vector].
+    Trap,
+    /// Halt the core with the instruction pointer left at the instruction.
+    Halt,
+    /// Stop running and return the decode error.
+    #[default]
+    Error
+}
+
+/// The emulated cycles an instruction takes. Used for performance modelling, so different instruction mixes can be
+/// compared by the total cycles of running them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostModel {
+    /// Cycles every executed instruction takes.
+    pub base_cost: u32,
+    /// Cycles added for each memory frame the instruction reads or writes, see [Instruction::memory_effects].
+    pub memory_cost: u32
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self { base_cost: 1, memory_cost: 1 }
+    }
+}
+
+impl CostModel {
+    /// Get the cycles an instruction takes when it accesses memory frames the given number of times.
+    pub fn cost(&self, memory_accesses: u64) -> u64 {
+        self.base_cost as u64 + self.memory_cost as u64 * memory_accesses
+    }
+}
+
+#[derive(Debug)]
+pub enum StepError {
+    /// The instruction at the instruction pointer could not be fetched.
+    Fetch(FetchError),
+    /// The instruction was fetched but its operation failed.
+    Execute(OperationExecuteError)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreError {
+    /// The instruction does not have the operand its destination selects.
+    Destination(DestinationError),
+    /// The destination is a constant, which has no location to store to.
+    Constant,
+    /// The destination refers to a register that does not exist.
+    Register(RegisterError),
+    /// Writing to the memory destination failed.
+    Memory(GetError)
+}
+
+/// Every register of a processor. Holds the general purpose registers along with the special registers and contains
+/// the rules for accessing them by code and by width.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegisterFile {
+    /// General purpose registers. Prefer [RegisterFile::get] and [RegisterFile::set] when the index comes from decoded
+    /// instruction bits.
+    pub general: [u64; REGISTERS_COUNT],
+    /// Status flags produced by operations.
+    pub flags: u64,
+    /// Address of the next instruction to execute.
+    pub instruction_pointer: u64,
+    /// Address of the top of the stack.
+    pub stack_pointer: u64
+}
+
+impl RegisterFile {
+    /// Get the assembly name of a general purpose register. [None] is returned if the code is not smaller than
+    /// [REGISTERS_COUNT].
+    /// ```
+    /// use atln_processor::emulator::processor::processor::{RegisterFile, REGISTERS_COUNT};
+    ///
+    /// assert_eq!(RegisterFile::mnemonic(0), Some("r0"));
+    /// assert_eq!(RegisterFile::mnemonic(REGISTERS_COUNT as u8), None);
+    ///
+    /// // Every name is unique and leads back to its code.
+    /// for code in 0..REGISTERS_COUNT as u8 {
+    ///     assert_eq!(RegisterFile::from_mnemonic(RegisterFile::mnemonic(code).unwrap()), Some(code));
+    /// }
+    ///
+    /// assert_eq!(RegisterFile::from_mnemonic("r8"), None);
+    /// ```
+    pub fn mnemonic(index: u8) -> Option<&'static str> {
+        REGISTER_MNEMONICS.get(index as usize).copied()
+    }
+
+    /// Get the code of a general purpose register from its assembly name. [None] is returned if no register has the
+    /// name.
+    pub fn from_mnemonic(mnemonic: &str) -> Option<u8> {
+        REGISTER_MNEMONICS.iter().position(|name| *name == mnemonic).map(|index| index as u8)
+    }
+
+    /// Read a general purpose register. If the index is not smaller than [REGISTERS_COUNT] then
+    /// [Err(RegisterError::OutOfBounds)] is returned instead of panicking.
+    pub fn get(&self, index: u8) -> Result<u64, RegisterError> {
+        match self.general.get(index as usize) {
+            Some(value) => Ok(*value),
+            None => Err(RegisterError::OutOfBounds)
+        }
+    }
+
+    /// Write to a general purpose register. If the index is not smaller than [REGISTERS_COUNT] then
+    /// [Err(RegisterError::OutOfBounds)] is returned and no register is modified.
+    pub fn set(&mut self, index: u8, value: u64) -> Result<(), RegisterError> {
+        match self.general.get_mut(index as usize) {
+            Some(register) => *register = value,
+            None => return Err(RegisterError::OutOfBounds)
+        };
+
+        Ok(())
+    }
+
+    /// Read the least significant bytes of a general purpose register as data of a size.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::RegisterFile;
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut registers = RegisterFile::default();
+    /// registers.general[1] = 0x1122_3344_5566_7788;
+    ///
+    /// assert!(matches!(registers.read(1, Size::Byte).unwrap(), Data::Byte(0x88)));
+    /// assert!(matches!(registers.read(1, Size::Dual).unwrap(), Data::Dual(0x5566_7788)));
+    /// assert!(matches!(registers.read(1, Size::Quad).unwrap(), Data::Quad(0x1122_3344_5566_7788)));
+    /// ```
+    pub fn read(&self, index: u8, size: number::Size) -> Result<number::Data, RegisterError> {
+        // Unwrapping is safe because every size has a valid exponent.
+        Ok(number::Data::from_exponent_selecting(size.exponent(), self.get(index)?).unwrap())
+    }
+
+    /// Write data into the least significant bytes of a general purpose register. The data is truncated to the size
+    /// and the more significant bytes of the register are preserved.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::RegisterFile;
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut registers = RegisterFile::default();
+    /// registers.general[1] = 0x1122_3344_5566_7788;
+    ///
+    /// registers.write(1, Size::Word, Data::Quad(0xAABB_CCDD)).unwrap();
+    /// assert_eq!(registers.general[1], 0x1122_3344_5566_CCDD);
+    ///
+    /// registers.write(1, Size::Quad, Data::Byte(1)).unwrap();
+    /// assert_eq!(registers.general[1], 1);
+    /// ```
+    pub fn write(&mut self, index: u8, size: number::Size, value: number::Data) -> Result<(), RegisterError> {
+        let mask = u64::MAX >> (64 - size.size() as u32 * 8);
+        let register = self.get(index)?;
+
+        self.set(index, (register & !mask) | (value.quad() & mask))
+    }
+}
+
+/// The status flags an operation produces. Operations compute their flags through [Flags::from_arithmetic] or
+/// [Flags::from_result] so every operation agrees on what each flag means. Stored in [RegisterFile::flags] as bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Flags {
+    /// The result is 0.
+    pub zero: bool,
+    /// An unsigned addition carried out of the width or an unsigned subtraction borrowed.
+    pub carry: bool,
+    /// The most significant bit of the result is set, so it is negative as a two's complement number.
+    pub sign: bool,
+    /// A two's complement operation gave a result that does not fit in the width.
+    pub overflow: bool
+}
+
+impl Flags {
+    /// Flags for an arithmetic operation. The zero and sign flags are computed from the result at the width, the carry
+    /// and overflow flags come from the operation as only it knows how they were produced.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::Flags;
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let flags = Flags::from_arithmetic(&Data::Byte(0x80), Size::Byte, true, false);
+    /// assert_eq!(flags, Flags { zero: false, carry: true, sign: true, overflow: false });
+    ///
+    /// // Bytes past the width are ignored.
+    /// let flags = Flags::from_arithmetic(&Data::Word(0x0100), Size::Byte, false, true);
+    /// assert_eq!(flags, Flags { zero: true, carry: false, sign: false, overflow: true });
+    /// ```
+    pub fn from_arithmetic(result: &number::Data, width: number::Size, carry: bool, overflow: bool) -> Self {
+        let bits = width.size() as u32 * 8;
+        let value = result.quad() & (u64::MAX >> (64 - bits));
+
+        Self {
+            zero: value == 0,
+            carry,
+            sign: value >> (bits - 1) == 1,
+            overflow
+        }
+    }
+
+    /// Flags for an operation such as a logical one that cannot carry or overflow. Only the zero and sign flags can be
+    /// set.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::Flags;
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// assert_eq!(Flags::from_result(&Data::Byte(0), Size::Byte), Flags { zero: true, ..Flags::default() });
+    /// assert_eq!(Flags::from_result(&Data::Word(0x8000), Size::Word), Flags { sign: true, ..Flags::default() });
+    /// assert_eq!(Flags::from_result(&Data::Word(0x8000), Size::Dual), Flags::default());
+    /// ```
+    pub fn from_result(result: &number::Data, width: number::Size) -> Self {
+        Self::from_arithmetic(result, width, false, false)
+    }
+
+    /// Encode the flags as the bits stored in [RegisterFile::flags].
+    /// ```
+    /// use atln_processor::emulator::processor::processor::{CARRY_FLAG, Flags, ZERO_FLAG};
+    ///
+    /// let flags = Flags { zero: true, carry: true, ..Flags::default() };
+    ///
+    /// assert_eq!(flags.bits(), ZERO_FLAG | CARRY_FLAG);
+    /// assert_eq!(Flags::from_bits(flags.bits()), flags);
+    /// ```
+    pub fn bits(&self) -> u64 {
+        let mut bits = 0;
+        if self.zero { bits |= ZERO_FLAG }
+        if self.carry { bits |= CARRY_FLAG }
+        if self.sign { bits |= SIGN_FLAG }
+        if self.overflow { bits |= OVERFLOW_FLAG }
+        bits
+    }
+
+    /// Decode the bits stored in [RegisterFile::flags]. Bits that are not flags are ignored.
+    pub fn from_bits(bits: u64) -> Self {
+        Self {
+            zero: bits & ZERO_FLAG != 0,
+            carry: bits & CARRY_FLAG != 0,
+            sign: bits & SIGN_FLAG != 0,
+            overflow: bits & OVERFLOW_FLAG != 0
+        }
+    }
+}
+
+/// A relation between the operands of the comparison that produced a set of flags. Conditional operations test their
+/// condition with [Condition::evaluate] so every one of them agrees on what each condition means. The relations are
+/// named as if the flags came from subtracting the dynamic operand from the static operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// The operands are equal.
+    Zero,
+    /// The operands are not equal.
+    NotZero,
+    /// The static operand is below the dynamic operand when both are unsigned.
+    Carry,
+    /// The static operand is above or equal to the dynamic operand when both are unsigned.
+    NotCarry,
+    /// The static operand is above the dynamic operand when both are unsigned.
+    Above,
+    /// The static operand is below or equal to the dynamic operand when both are unsigned.
+    BelowEqual,
+    /// The result is negative.
+    Sign,
+    /// The result is positive or 0.
+    NotSign,
+    /// The result does not fit in the width as a two's complement number.
+    Overflow,
+    /// The result fits in the width as a two's complement number.
+    NotOverflow,
+    /// The static operand is less than the dynamic operand when both are signed.
+    Less,
+    /// The static operand is less than or equal to the dynamic operand when both are signed.
+    LessEqual,
+    /// The static operand is greater than the dynamic operand when both are signed.
+    Greater,
+    /// The static operand is greater than or equal to the dynamic operand when both are signed.
+    GreaterEqual
+}
+
+impl Condition {
+    /// Check whether the condition holds for the flags. Unsigned relations only depend on the carry and zero flags.
+    /// Signed relations compare the sign flag against the overflow flag because an overflowing subtraction gives a
+    /// result with the wrong sign.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::{Condition, Flags};
+    ///
+    /// // 1 - 2 as bytes is 0xFF, which borrows but does not overflow.
+    /// let flags = Flags { zero: false, carry: true, sign: true, overflow: false };
+    /// assert!(Condition::Less.evaluate(&flags));
+    /// assert!(Condition::Carry.evaluate(&flags));
+    ///
+    /// // 0x80 - 1 as bytes is 0x7F. Unsigned it is 128 - 1, signed it is -128 - 1 which overflows.
+    /// let flags = Flags { zero: false, carry: false, sign: false, overflow: true };
+    /// assert!(Condition::Less.evaluate(&flags));
+    /// assert!(!Condition::Carry.evaluate(&flags));
+    /// assert!(Condition::Above.evaluate(&flags));
+    ///
+    /// // 0x7F - 0xFF as bytes is 0x80. Unsigned it is 127 - 255, signed it is 127 - -1 which overflows.
+    /// let flags = Flags { zero: false, carry: true, sign: true, overflow: true };
+    /// assert!(!Condition::Less.evaluate(&flags));
+    /// assert!(Condition::Greater.evaluate(&flags));
+    /// assert!(Condition::Carry.evaluate(&flags));
+    ///
+    /// let flags = Flags { zero: true, ..Flags::default() };
+    /// assert!(Condition::LessEqual.evaluate(&flags));
+    /// assert!(Condition::BelowEqual.evaluate(&flags));
+    /// assert!(!Condition::Greater.evaluate(&flags));
+    /// assert!(!Condition::Above.evaluate(&flags));
+    /// ```
+    pub fn evaluate(&self, flags: &Flags) -> bool {
+        match self {
+            Self::Zero => flags.zero,
+            Self::NotZero => !flags.zero,
+            Self::Carry => flags.carry,
+            Self::NotCarry => !flags.carry,
+            Self::Above => !flags.carry && !flags.zero,
+            Self::BelowEqual => flags.carry || flags.zero,
+            Self::Sign => flags.sign,
+            Self::NotSign => !flags.sign,
+            Self::Overflow => flags.overflow,
+            Self::NotOverflow => !flags.overflow,
+            Self::Less => flags.sign != flags.overflow,
+            Self::LessEqual => flags.zero || flags.sign != flags.overflow,
+            Self::Greater => !flags.zero && flags.sign == flags.overflow,
+            Self::GreaterEqual => flags.sign == flags.overflow
+        }
+    }
+}
+
+impl Context {
+    /// Read a register. If the index is not smaller than [REGISTERS_COUNT] then [Err(RegisterError::OutOfBounds)] is
+    /// returned instead of panicking.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::{Context, RegisterError};
+    ///
+    /// let mut context = Context::default();
+    /// context.registers.general[7] = 10;
+    ///
+    /// assert_eq!(context.register(7), Ok(10));
+    /// assert_eq!(context.register(8), Err(RegisterError::OutOfBounds));
+    /// ```
+    pub fn register(&self, index: u8) -> Result<u64, RegisterError> {
+        self.registers.get(index)
+    }
+
+    /// Write to a register. If the index is not smaller than [REGISTERS_COUNT] then
+    /// [Err(RegisterError::OutOfBounds)] is returned and no register is modified.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::{Context, RegisterError};
+    ///
+    /// let mut context = Context::default();
+    ///
+    /// assert_eq!(context.set_register(0, 5), Ok(()));
+    /// assert_eq!(context.registers.general[0], 5);
+    /// assert_eq!(context.set_register(255, 5), Err(RegisterError::OutOfBounds));
+    /// ```
+    pub fn set_register(&mut self, index: u8, value: u64) -> Result<(), RegisterError> {
+        self.registers.set(index, value)
+    }
+}
+
+impl Default for Core {
+    fn default() -> Self {
+        Self::with_context(ContextBuilder::default().build())
+    }
 }
 
 impl Core {
-    /// Execute an instruction and see if the processor must halt. Doing this could modify the execution context.
-    pub fn execute(_instruction: &Instruction, _memory: &mut Memory, ports: &mut Ports) -> bool {
-        todo!();
+    /// Create a core which starts with a prepared execution context. Use [ContextBuilder] to prepare the context. A copy
+    /// of the context is kept for [Core::reset].
+    pub fn with_context(context: Context) -> Self {
+        Self {
+            entry_point: context.registers.instruction_pointer,
+            initial_stack_pointer: context.registers.stack_pointer,
+            initial_context: context.clone(),
+            context,
+            require_aligned_ip: false,
+            halted: false,
+            on_decode_error: DecodePolicy::default(),
+            illegal_instruction_vector: 0,
+            cost_model: CostModel::default(),
+            cycles: 0,
+            histogram: None
+        }
+    }
+
+    /// Return the core to its initial state so a program can be run again without constructing a new core. The context
+    /// is restored to [Core::initial_context], so preset registers and the mode come back and everything else
+    /// including the flags is cleared. The instruction pointer and stack pointer are set to the entry point and initial
+    /// stack pointer.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::{ContextBuilder, Core};
+    ///
+    /// let mut core = Core::with_context(ContextBuilder::default()
+    ///     .instruction_pointer(0x400)
+    ///     .stack_pointer(0x2000)
+    ///     .register(5, 0xFF).unwrap()
+    ///     .virtual_mode(true)
+    ///     .build());
+    /// let initial = core.clone();
+    ///
+    /// core.context.registers.general[3] = 10;
+    /// core.context.registers.general[5] = 0;
+    /// core.context.registers.flags = 1;
+    /// core.context.registers.instruction_pointer = 0x480;
+    /// core.context.registers.stack_pointer = 0x1FF0;
+    /// core.context.virtual_mode = false;
+    ///
+    /// core.reset();
+    /// assert_eq!(core, initial);
+    /// assert_eq!(core.context.registers.instruction_pointer, 0x400);
+    /// assert_eq!(core.context.registers.stack_pointer, 0x2000);
+    /// assert_eq!(core.context.registers.general[5], 0xFF);
+    /// assert!(core.context.virtual_mode);
+    /// ```
+    pub fn reset(&mut self) {
+        self.context = self.initial_context.clone();
+        self.context.registers.instruction_pointer = self.entry_point;
+        self.context.registers.stack_pointer = self.initial_stack_pointer;
+        self.halted = false;
+        self.cycles = 0;
+    }
+
+    /// Decode the instruction at the instruction pointer. The instruction pointer is translated if the core is in
+    /// virtual mode.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    /// use atln_processor::emulator::processor::processor::{ContextBuilder, Core, FetchError};
+    ///
+    /// // An addition with register operands starting at address 1.
+    /// let mut memory = Memory::from(vec![ 0, 0b000000_0_0, 0b0000_00_00, 0b00_001_010 ]);
+    /// let mut core = Core::with_context(ContextBuilder::default().instruction_pointer(1).build());
+    ///
+    /// assert!(core.fetch(&mut memory).is_ok());
+    ///
+    /// core.require_aligned_ip = true;
+    /// assert!(matches!(core.fetch(&mut memory), Err(FetchError::UnalignedInstructionPointer)));
+    /// ```
+    pub fn fetch(&self, memory: &mut impl MemoryAccess) -> Result<Instruction, FetchError> {
+        let instruction_pointer = self.context.registers.instruction_pointer;
+        if self.require_aligned_ip && instruction_pointer & 1 != 0 { return Err(FetchError::UnalignedInstructionPointer) }
+
+        let mut source = AccessSource { memory, translate: self.context.virtual_mode };
+
+        match Instruction::from_source(&mut source, instruction_pointer) {
+            Ok(instruction) => Ok(instruction),
+            Err(error) => Err(FetchError::Decode(error))
+        }
+    }
+
+    /// Fetch the instruction at the instruction pointer, move the instruction pointer past it and then execute it. The
+    /// instruction pointer is moved before executing so operations see the address of the next instruction. The cost
+    /// of the instruction is added to [Core::cycles] if it executes successfully.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    /// use atln_processor::emulator::processor::processor::{Core, StepError};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::NO_OPERATION_ENCODED;
+    ///
+    /// let mut memory = Memory::from(NO_OPERATION_ENCODED.to_vec());
+    /// let mut core = Core::default();
+    ///
+    /// core.step(&mut memory).unwrap();
+    /// assert_eq!(core.context.registers.instruction_pointer, NO_OPERATION_ENCODED.len() as u64);
+    ///
+    /// // Nothing is left to fetch.
+    /// assert!(matches!(core.step(&mut memory), Err(StepError::Fetch(_))));
+    /// ```
+    pub fn step(&mut self, memory: &mut impl MemoryAccess) -> Result<(), StepError> {
+        let instruction = match self.fetch(memory) {
+            Ok(instruction) => instruction,
+            Err(error) => return Err(StepError::Fetch(error))
+        };
+
+        // Found before executing because executing can change the registers the memory operand is resolved with.
+        let memory_accesses = match instruction.memory_effects(&self.context) {
+            Ok(effects) => (effects.reads.len() + effects.writes.len()) as u64,
+            Err(_) => 0
+        };
+
+        let length = instruction.encode().len() as u64;
+        self.context.registers.instruction_pointer = self.context.registers.instruction_pointer.wrapping_add(length);
+
+        match instruction.extension.execute(&instruction, self, memory) {
+            Ok(()) => {
+                self.cycles += self.cost_model.cost(memory_accesses);
+                if let Some(histogram) = &mut self.histogram { *histogram.entry(instruction.extension).or_insert(0) += 1 }
+                Ok(())
+            },
+            Err(error) => Err(StepError::Execute(error))
+        }
+    }
+
+    /// Get the total emulated cycles of the instructions executed since the core was created or last reset. Each
+    /// instruction costs what [Core::cost_model] gives for it.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    /// use atln_processor::emulator::processor::processor::{CostModel, Core};
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::control::Control;
+    /// use atln_processor::number;
+    ///
+    /// let add = |destination: Destination, x_dynamic: Dynamic| Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Add),
+    ///     data: Some(Data { width: number::Size::Quad, destination, synchronous: false, operands: Operands::all(1, x_dynamic) })
+    /// };
+    ///
+    /// let program = [
+    ///     // No memory accesses.
+    ///     add(Destination::Static, Dynamic::Constant(number::Data::Byte(5))),
+    ///     // Reads memory.
+    ///     add(Destination::Static, Dynamic::Memory(number::Data::Byte(0x40))),
+    ///     // Reads and writes memory.
+    ///     add(Destination::Dynamic, Dynamic::Memory(number::Data::Byte(0x40))),
+    ///     Instruction { extension: Extension::Control(Control::Halt), data: None }
+    /// ];
+    ///
+    /// let mut bytes: Vec<u8> = program.iter().flat_map(Instruction::encode).collect();
+    /// bytes.resize(0x48, 0);
+    ///
+    /// let mut core = Core::default();
+    /// core.cost_model = CostModel { base_cost: 2, memory_cost: 3 };
+    /// core.run(&mut Memory::from(bytes)).unwrap();
+    ///
+    /// assert_eq!(core.cycles(), 2 + (2 + 3) + (2 + 3 * 2) + 2);
+    ///
+    /// core.reset();
+    /// assert_eq!(core.cycles(), 0);
+    /// ```
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Start counting how many times each operation is executed. Counting is off by default to avoid the overhead.
+    pub fn enable_histogram(&mut self) {
+        if self.histogram.is_none() { self.histogram = Some(OperationHistogram::new()) }
+    }
+
+    /// Get how many times each operation was executed since [Core::enable_histogram] or [Core::reset_histogram] was
+    /// called. [None] is returned if counting is off.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    /// use atln_processor::emulator::processor::processor::Core;
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::control::Control;
+    /// use atln_processor::number;
+    ///
+    /// let instruction = |operation: Arithmetic, x_dynamic: Dynamic| Instruction {
+    ///     extension: Extension::Arithmetic(operation),
+    ///     data: Some(Data {
+    ///         width: number::Size::Quad,
+    ///         destination: Destination::Static,
+    ///         synchronous: false,
+    ///         operands: Operands::all(1, x_dynamic)
+    ///     })
+    /// };
+    ///
+    /// // Two additions and a move of an address into a register.
+    /// let program = [
+    ///     instruction(Arithmetic::Add, Dynamic::Constant(number::Data::Byte(5))),
+    ///     instruction(Arithmetic::Add, Dynamic::Register(1)),
+    ///     instruction(Arithmetic::LoadEffectiveAddress, Dynamic::Memory(number::Data::Word(0x100))),
+    ///     Instruction { extension: Extension::Control(Control::Halt), data: None }
+    /// ];
+    ///
+    /// let code: Vec<u8> = program.iter().flat_map(Instruction::encode).collect();
+    /// let mut core = Core::default();
+    /// assert!(core.histogram().is_none());
+    ///
+    /// core.enable_histogram();
+    /// core.run(&mut Memory::from(code)).unwrap();
+    ///
+    /// let histogram = core.histogram().unwrap();
+    /// assert_eq!(histogram[&Extension::Arithmetic(Arithmetic::Add)], 2);
+    /// assert_eq!(histogram[&Extension::Arithmetic(Arithmetic::LoadEffectiveAddress)], 1);
+    /// assert_eq!(histogram[&Extension::Control(Control::Halt)], 1);
+    /// assert_eq!(histogram.len(), 3);
+    ///
+    /// core.reset_histogram();
+    /// assert!(core.histogram().unwrap().is_empty());
+    /// ```
+    pub fn histogram(&self) -> Option<&OperationHistogram> {
+        self.histogram.as_ref()
+    }
+
+    /// Clear the counts of executed operations. Counting stays on if it was on.
+    pub fn reset_histogram(&mut self) {
+        if let Some(histogram) = &mut self.histogram { histogram.clear() }
+    }
+
+    /// Step through instructions until the core is halted. Stepping stops at the first error, which is returned, unless
+    /// it is a decode error and [Core::on_decode_error] selects another [DecodePolicy].
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, Memory};
+    /// use atln_processor::emulator::processor::processor::Core;
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::control::Control;
+    /// use atln_processor::number;
+    ///
+    /// let add = |destination: Destination, x_dynamic: Dynamic| Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Add),
+    ///     data: Some(Data { width: number::Size::Quad, destination, synchronous: false, operands: Operands::all(1, x_dynamic) })
+    /// };
+    ///
+    /// let program = [
+    ///     // Load 5 into r1, add 10 to it and then store it to address 0x40.
+    ///     add(Destination::Static, Dynamic::Constant(number::Data::Byte(5))),
+    ///     add(Destination::Static, Dynamic::Constant(number::Data::Byte(10))),
+    ///     add(Destination::Dynamic, Dynamic::Memory(number::Data::Byte(0x40))),
+    ///     Instruction { extension: Extension::Control(Control::Halt), data: None }
+    /// ];
+    ///
+    /// let code: Vec<u8> = program.iter().flat_map(Instruction::encode).collect();
+    /// let mut bytes = code.clone();
+    /// bytes.resize(0x48, 0);
+    ///
+    /// let mut memory = Memory::from(bytes);
+    /// let mut core = Core::default();
+    ///
+    /// core.run(&mut memory).unwrap();
+    ///
+    /// assert!(core.halted);
+    /// assert_eq!(core.context.registers.general[1], 15);
+    /// assert_eq!(core.context.registers.instruction_pointer, code.len() as u64);
+    /// assert_eq!(memory.get(Frame { address: 0x40, size: number::Size::Quad }, false), Ok(number::Data::Quad(15)));
+    /// assert_eq!(memory.bytes[..code.len()], code[..]);
+    /// ```
+    pub fn run(&mut self, memory: &mut impl MemoryAccess) -> Result<(), StepError> {
+        while !self.halted {
+            match self.step(memory) {
+                Ok(()) => {},
+                Err(StepError::Fetch(FetchError::Decode(error))) => match self.on_decode_error {
+                    DecodePolicy::Trap => self.context.registers.instruction_pointer = self.illegal_instruction_vector,
+                    DecodePolicy::Halt => self.halted = true,
+                    DecodePolicy::Error => return Err(StepError::Fetch(FetchError::Decode(error)))
+                },
+                Err(error) => return Err(error)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the result of an instruction to its destination operand. The result is truncated to the instruction's
+    /// width. Registers keep their bytes past the width, and memory destinations are written through a frame of the
+    /// width, translated if the core is in virtual mode.
+    /// ```
+    /// use atln_processor::emulator::memory::{Frame, Memory};
+    /// use atln_processor::emulator::processor::processor::Core;
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number;
+    ///
+    /// let mut memory = Memory::from(vec![0u8; 16]);
+    /// let mut core = Core::default();
+    ///
+    /// let mut instruction = Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Add),
+    ///     data: Some(Data {
+    ///         width: number::Size::Word,
+    ///         destination: Destination::Dynamic,
+    ///         synchronous: false,
+    ///         operands: Operands::all(1, Dynamic::Memory(number::Data::Byte(8)))
+    ///     })
+    /// };
+    ///
+    /// core.store_result(&instruction, number::Data::Dual(0xAABB_CCDD), &mut memory).unwrap();
+    /// assert_eq!(memory.bytes[8..12], [ 0xDD, 0xCC, 0, 0 ]);
+    ///
+    /// // The static operand is always a register.
+    /// instruction.data.as_mut().unwrap().destination = Destination::Static;
+    /// core.store_result(&instruction, number::Data::Dual(0xAABB_CCDD), &mut memory).unwrap();
+    /// assert_eq!(core.context.registers.general[1], 0xCCDD);
+    /// ```
+    pub fn store_result(&mut self, instruction: &Instruction, value: number::Data, memory: &mut impl MemoryAccess) -> Result<(), StoreError> {
+        let destination = match instruction.destination_ref() {
+            Ok(destination) => destination,
+            Err(error) => return Err(StoreError::Destination(error))
+        };
+
+        // Unwrapping is safe because a destination is only found if there is data.
+        let width = instruction.data.as_ref().unwrap().width.clone();
+
+        let register = match destination {
+            OperandRef::Static(register) => register,
+            OperandRef::Dynamic(x_dynamic) => match x_dynamic.resolve(&self.context, width.clone()) {
+                Ok(ResolvedOperand::Register(register)) => register,
+                Ok(ResolvedOperand::Memory(frame)) => return match memory.set(frame, self.context.virtual_mode, value) {
+                    Ok(()) => Ok(()),
+                    Err(error) => Err(StoreError::Memory(error))
+                },
+                Ok(ResolvedOperand::Constant(_)) => return Err(StoreError::Constant),
+                Err(error) => return Err(StoreError::Register(error))
+            }
+        };
+
+        match self.context.registers.write(register, width, value) {
+            Ok(()) => Ok(()),
+            Err(error) => Err(StoreError::Register(error))
+        }
     }
 }
@@ -31,12 +31,18 @@
 pub mod operand;
 pub mod operation;
 
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::io;
-use std::io::Read;
-use emulator::processor::processor::instruction::operand::OperandsPresence;
+use std::io::{Cursor, Read, Write};
+use std::ops::Range;
+use emulator::memory::{Frame, GetError, Memory, MemoryAccess, MemoryCursor};
+use emulator::processor::processor::instruction::operand::{OperandsPresence, REGISTER_ADDRESSING};
 use crate::number;
-use super::instruction::operand::{Destination, Dynamic, Operand, Operands, OperandsConstructError};
-use super::instruction::operation::{Extension, ExtensionFromCodeInvalid, Operation};
+use super::instruction::operand::{AllPresent, Destination, Dynamic, Operand, OperandRef, Operands, OperandsConstructError, ResolvedOperand};
+use super::{Context, RegisterError};
+use super::instruction::operation::{Extension, ExtensionFromCodeInvalid};
+use super::instruction::operation::arithmetic::Arithmetic;
 use crate::utility::{Coded, Encodable};
 
 // region: Binary processor bit masks
@@ -51,6 +57,16 @@ pub const REGISTERS_STATIC_OPERAND_MASK    : u8 = 0b00_111_000;
 pub const REGISTERS_DYNAMIC_OPERAND_MASK   : u8 = 0b00_000_111;
 // endregion
 
+/// A named field of an encoded byte sequence. Used to inspect how bytes are broken up into fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldView {
+    pub name: &'static str,
+    /// Bits the field occupies. Bit 0 is the least significant bit of the last byte.
+    pub bits: Range<u8>,
+    /// Value of the field after being shifted down to the least significant bit.
+    pub value: u8
+}
+
 /// Structured data from the driver bytes. All data generated by inherent functions are unchecked. Contains utility
 /// functions for coding driver bytes.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -97,6 +113,36 @@ impl Driver {
             immediate_exponent: driver1.extract_immediate_exponent(),
         }
     }
+
+    /// Break the driver bytes up into their fields. The bytes are treated as a single big endian number, so driver 0
+    /// occupies bits 8 to 16 and driver 1 occupies bits 0 to 8.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::{Driver, FieldView};
+    ///
+    /// let fields = Driver::describe([0b001010_1_0, 0b1111_10_01]);
+    ///
+    /// assert_eq!(fields, vec![
+    ///     FieldView { name: "extension", bits: 10..16, value: 0b001010 },
+    ///     FieldView { name: "synchronise", bits: 9..10, value: 1 },
+    ///     FieldView { name: "dynamic_destination", bits: 8..9, value: 0 },
+    ///     FieldView { name: "operation", bits: 4..8, value: 0b1111 },
+    ///     FieldView { name: "addressing", bits: 2..4, value: 0b10 },
+    ///     FieldView { name: "immediate_exponent", bits: 0..2, value: 0b01 }
+    /// ]);
+    /// ```
+    pub fn describe(bytes: [u8; 2]) -> Vec<FieldView> {
+        let driver0 = bytes[0];
+        let driver1 = bytes[1];
+
+        vec![
+            FieldView { name: "extension", bits: 10..16, value: driver0.extract_extension() },
+            FieldView { name: "synchronise", bits: 9..10, value: driver0.extract_synchronise() as u8 },
+            FieldView { name: "dynamic_destination", bits: 8..9, value: driver0.extract_dynamic_destination() as u8 },
+            FieldView { name: "operation", bits: 4..8, value: driver1.extract_operation() },
+            FieldView { name: "addressing", bits: 2..4, value: driver1.extract_addressing() },
+            FieldView { name: "immediate_exponent", bits: 0..2, value: driver1.extract_immediate_exponent() }
+        ]
+    }
 }
 
 impl Encodable<[u8; 2]> for Driver {
@@ -358,6 +404,24 @@ impl Registers {
         }
     }
 
+    /// Break the registers byte up into its fields.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::{FieldView, Registers};
+    ///
+    /// assert_eq!(Registers::describe(0b10__011_101), vec![
+    ///     FieldView { name: "width", bits: 6..8, value: 0b10 },
+    ///     FieldView { name: "x_static", bits: 3..6, value: 0b011 },
+    ///     FieldView { name: "x_dynamic", bits: 0..3, value: 0b101 }
+    /// ]);
+    /// ```
+    pub fn describe(encoded: u8) -> Vec<FieldView> {
+        vec![
+            FieldView { name: "width", bits: 6..8, value: encoded.extract_width() },
+            FieldView { name: "x_static", bits: 3..6, value: encoded.extract_static() },
+            FieldView { name: "x_dynamic", bits: 0..3, value: encoded.extract_dynamic() }
+        ]
+    }
+
     /// Encode this registers data structure into a registers byte which contains the properties of register targeting.
     /// ```
     /// use atln_processor::emulator::processor::processor::instruction::Registers;
@@ -501,10 +565,19 @@ pub enum DataConstructError {
     Destination
 }
 
+impl From<io::Error> for DataConstructError {
+    fn from(value: io::Error) -> Self {
+        Self::StreamRead(value)
+    }
+}
+
 impl Data {
     /// Try to construct a data field from data with an operation and driver. The data structure contains information
     /// operands and how they should be handled and dealt with as well as addressing information for x_dynamic. This
     /// involves decoding the stream with [Registers].
+    ///
+    /// The registers byte is present whenever there are operands, so the width always comes from it, including when
+    /// only the static operand is expected. Only an expected dynamic operand reads an immediate.
     /// ```
     /// use std::io::Cursor;
     /// use atln_processor::emulator::processor::processor::instruction::{Data, Driver};
@@ -515,12 +588,11 @@ impl Data {
     /// let mut extension = Extension::Arithmetic(Arithmetic::Add);
     /// let extension_code = extension.code();
     ///
-    /// let operation = extension.operation();
-    /// let operation_code = operation.code();
+    /// let operation_code = extension.operation_code();
     ///
     /// let data = Data::new(
     ///     &mut Cursor::new([ 00_000_000 ]),
-    ///     operation,
+    ///     &extension.presence().unwrap(),
     ///     &Driver {
     ///         extension: extension_code,
     ///         operation: operation_code,
@@ -537,10 +609,7 @@ impl Data {
     pub fn new(stream: &mut impl Read, presence: &OperandsPresence, driver: &Driver) -> Result<Self, DataConstructError> {
         // Decode registers byte.
         let mut data_encoded = [0u8; 1];
-        match stream.read(&mut data_encoded) {
-            Ok(length) => if length != data_encoded.len() { return Err(DataConstructError::Length); },
-            Err(error) => return Err(DataConstructError::StreamRead(error))
-        };
+        if stream.read(&mut data_encoded)? != data_encoded.len() { return Err(DataConstructError::Length); }
 
         let registers = Registers::new(data_encoded[0]);
         let destination = if driver.dynamic_destination { Destination::Dynamic } else { Destination::Static };
@@ -552,10 +621,11 @@ impl Data {
         };
 
         // Prevent the invalid instruction configuration which involves pointing to a constant dynamic operand as the
-        // destination operand.
-        if let Some(x_dynamic) = operands.x_dynamic() { if let Destination::Dynamic = destination { if let Dynamic::Constant(_) = x_dynamic {
-            return Err(DataConstructError::Destination);
-        }}}
+        // destination operand, or to a dynamic operand that is not there.
+        if let Destination::Dynamic = destination { match operands.x_dynamic() {
+            Some(Dynamic::Constant(_)) | None => return Err(DataConstructError::Destination),
+            Some(_) => ()
+        }}
 
         // Construct data.
         Ok(Data {
@@ -567,7 +637,7 @@ impl Data {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Instruction {
     pub extension: Extension,
     pub data: Option<Data>
@@ -582,7 +652,118 @@ pub enum InstructionConstructError {
     /// The extension and or operation are invalid.
     InvalidCode(ExtensionFromCodeInvalid),
     /// Failed to construct the data field of the instruction.
-    Data(DataConstructError)
+    Data(DataConstructError),
+    /// The operation takes no operands but the driver sets fields that only describe operands.
+    InconsistentDriver,
+    /// The immediate is wider than [DecodeOptions::max_immediate_size] allows.
+    ImmediateTooWide,
+    /// Decoding the instruction starting at the byte offset into a program failed with the contained error.
+    At { offset: usize, error: Box<InstructionConstructError> }
+}
+
+/// Restrictions applied when decoding so that a target which only implements part of the instruction set can reject
+/// instructions it does not support.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// The widest immediate allowed. Defaults to [number::Size::Quad] which allows all widths.
+    pub max_immediate_size: number::Size
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self { max_immediate_size: number::Size::Quad }
+    }
+}
+
+impl From<io::Error> for InstructionConstructError {
+    fn from(value: io::Error) -> Self {
+        Self::StreamRead(value)
+    }
+}
+
+/// Somewhere instructions can be read from by address. This lets an instruction be decoded from memory without
+/// constructing a stream positioned at the instruction first.
+pub trait InstructionSource {
+    /// Read bytes starting at the address into the buffer and return how many bytes were read. Fewer bytes than the
+    /// buffer length are read if the source ends before the buffer is filled.
+    fn read_at(&mut self, address: u64, buffer: &mut [u8]) -> io::Result<usize>;
+}
+
+impl<T: AsRef<[u8]>> InstructionSource for Cursor<T> {
+    fn read_at(&mut self, address: u64, buffer: &mut [u8]) -> io::Result<usize> {
+        self.set_position(address);
+        self.read(buffer)
+    }
+}
+
+impl InstructionSource for Memory {
+    /// Addresses are physical. Reading stops at the first byte that cannot be read.
+    fn read_at(&mut self, address: u64, buffer: &mut [u8]) -> io::Result<usize> {
+        Ok(read_memory_bytes(self, address, false, buffer).0)
+    }
+}
+
+impl<'a> InstructionSource for MemoryCursor<'a> {
+    /// Addresses are translated if the cursor translates. Reading stops at the first byte that cannot be read and the
+    /// error is kept in [MemoryCursor::get_error].
+    fn read_at(&mut self, address: u64, buffer: &mut [u8]) -> io::Result<usize> {
+        let (length, error) = read_memory_bytes(self.memory, address, self.translate, buffer);
+        self.get_error = error;
+        Ok(length)
+    }
+}
+
+/// Reads instructions from any [MemoryAccess] implementation.
+#[derive(Debug)]
+pub struct AccessSource<'a, M: MemoryAccess> {
+    pub memory: &'a mut M,
+    /// Whether to translate addresses.
+    pub translate: bool
+}
+
+impl<'a, M: MemoryAccess> InstructionSource for AccessSource<'a, M> {
+    /// Reading stops at the first byte that cannot be read.
+    fn read_at(&mut self, address: u64, buffer: &mut [u8]) -> io::Result<usize> {
+        Ok(read_memory_bytes(self.memory, address, self.translate, buffer).0)
+    }
+}
+
+/// Read memory a byte at a time so that reads may cross pages and need no alignment. Returns the number of bytes read
+/// and the error that stopped the read if there was one.
+fn read_memory_bytes(memory: &mut impl MemoryAccess, address: u64, r#virtual: bool, buffer: &mut [u8]) -> (usize, Option<GetError>) {
+    for (index, byte) in buffer.iter_mut().enumerate() {
+        let frame = Frame { address: address.wrapping_add(index as u64), size: number::Size::Byte };
+
+        match memory.get(frame, r#virtual) {
+            Ok(data) => *byte = data.quad() as u8,
+            Err(error) => return (index, Some(error))
+        }
+    }
+
+    (buffer.len(), None)
+}
+
+/// Reads an [InstructionSource] as a stream, advancing past the bytes read.
+struct SourceStream<'a, S: InstructionSource> {
+    source: &'a mut S,
+    address: u64
+}
+
+impl<'a, S: InstructionSource> Read for SourceStream<'a, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = self.source.read_at(self.address, buf)?;
+        self.address = self.address.wrapping_add(length as u64);
+        Ok(length)
+    }
+}
+
+/// Caused by relocating an absolute address out of the range of its immediate. Contains the index of the instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelocateError {
+    /// The address would become negative.
+    Underflow(usize),
+    /// The address would not fit in the width of its immediate.
+    Overflow(usize)
 }
 
 /// Caused by using a destination which corresponds to an operand that is not provided.
@@ -596,6 +777,15 @@ pub enum DestinationError {
     Dynamic
 }
 
+/// The memory an instruction accesses when it executes, found with [Instruction::memory_effects].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryEffects {
+    /// Frames that are read from.
+    pub reads: Vec<Frame>,
+    /// Frames that are written to.
+    pub writes: Vec<Frame>
+}
+
 impl Instruction {
     /// Use the driver, registers, and immediate to encode into a dynamic number of bytes. Encoding is variable
     /// length. The data is not validated here. To use an immediate, registers must be of the [Some] variant. If an
@@ -614,41 +804,309 @@ impl Instruction {
 
     // Decode an encoded binary stream into a processor instruction. TODO: Tests
     pub fn new(stream: &mut impl Read) -> Result<Self, InstructionConstructError> {
+        let mut instruction = Self::default();
+        instruction.decode_into(stream)?;
+        Ok(instruction)
+    }
+
+    /// Same as [Instruction::new] but the decoding is restricted by the options. If the immediate is wider than
+    /// [DecodeOptions::max_immediate_size], then [Err(InstructionConstructError::ImmediateTooWide)] is returned before
+    /// the immediate is read.
+    pub fn new_with(stream: &mut impl Read, options: &DecodeOptions) -> Result<Self, InstructionConstructError> {
+        let mut instruction = Self::default();
+        instruction.decode_into_with(stream, options)?;
+        Ok(instruction)
+    }
+
+    /// Same as [Instruction::new] but the decoded instruction overwrites this one, so a single instruction can be reused
+    /// across decodes. If decoding fails then this instruction is left unchanged.
+    pub fn decode_into(&mut self, stream: &mut impl Read) -> Result<(), InstructionConstructError> {
+        self.decode_into_with(stream, &DecodeOptions::default())
+    }
+
+    /// Same as [Instruction::decode_into] but the decoding is restricted by the options as with
+    /// [Instruction::new_with].
+    pub fn decode_into_with(&mut self, stream: &mut impl Read, options: &DecodeOptions) -> Result<(), InstructionConstructError> {
         // Decode driver bytes.
         let mut encoded_driver = [0u8; 2];
-
-        match stream.read(&mut encoded_driver) {
-            Ok(length) => if length != encoded_driver.len() { return Err(InstructionConstructError::Length) },
-            Err(error) => return Err(InstructionConstructError::StreamRead(error))
-        };
+        if stream.read(&mut encoded_driver)? != encoded_driver.len() { return Err(InstructionConstructError::Length) }
 
         let driver = Driver::new(encoded_driver);
 
-        let mut extension =  match Extension::from_codes(driver.extension, driver.operation) {
+        let extension =  match Extension::from_codes(driver.extension, driver.operation) {
             Ok(operation) => operation,
             Err(error) => return Err(InstructionConstructError::InvalidCode(error))
         };
 
+        let presence = match extension.presence() {
+            Some(presence) => presence,
+            None => {
+                Self::check_driver_without_data(&driver)?;
+
+                self.extension = extension;
+                self.data = None;
+                return Ok(())
+            }
+        };
+
         // Decode data bytes.
-        let operation = extension.operation();
-        
-        if let Some(presence) = operation.get_presence() {
-            let data: Option<Data> = match Data::new(stream, &presence, &driver) {
-                Ok(some) => Some(some),
-                Err(error) => return Err(InstructionConstructError::Data(error))
+        if presence.expects_dynamic() && driver.addressing != REGISTER_ADDRESSING && driver.immediate_exponent > options.max_immediate_size.exponent() {
+            return Err(InstructionConstructError::ImmediateTooWide)
+        }
+
+        let data = match Data::new(stream, &presence, &driver) {
+            Ok(data) => data,
+            Err(error) => return Err(InstructionConstructError::Data(error))
+        };
+
+        // Nothing is written until decoding succeeded, then the data already held is overwritten in place.
+        self.extension = extension;
+        match &mut self.data {
+            Some(existing) => *existing = data,
+            None => self.data = Some(data)
+        }
+
+        Ok(())
+    }
+
+    /// Decode only the driver bytes to find the operation, whether operands follow, and the total length of the
+    /// instruction in bytes. Only the 2 driver bytes are read from the stream, so this is quicker than
+    /// [Instruction::new] when scanning for instruction boundaries.
+    pub fn decode_header(stream: &mut impl Read) -> Result<(Extension, bool, u8), InstructionConstructError> {
+        let mut encoded_driver = [0u8; 2];
+        if stream.read(&mut encoded_driver)? != encoded_driver.len() { return Err(InstructionConstructError::Length) }
+
+        let driver = Driver::new(encoded_driver);
+        let extension = match Extension::from_codes(driver.extension, driver.operation) {
+            Ok(operation) => operation,
+            Err(error) => return Err(InstructionConstructError::InvalidCode(error))
+        };
+
+        let presence = match extension.presence() {
+            Some(presence) => presence,
+            None => {
+                Self::check_driver_without_data(&driver)?;
+                return Ok((extension, false, encoded_driver.len() as u8))
+            }
+        };
+
+        // The registers byte is always present with operands, the immediate only with a non register dynamic operand.
+        let mut length = encoded_driver.len() as u8 + 1;
+        if presence.expects_dynamic() && driver.addressing != REGISTER_ADDRESSING { length += 1 << driver.immediate_exponent }
+
+        Ok((extension, true, length))
+    }
+
+    /// Check the driver of an operation without operands does not describe a data byte. The destination and addressing
+    /// fields are only meaningful when a data byte follows, so if either is set then
+    /// [Err(InstructionConstructError::InconsistentDriver)] is returned.
+    fn check_driver_without_data(driver: &Driver) -> Result<(), InstructionConstructError> {
+        if driver.dynamic_destination || driver.addressing != 0 { return Err(InstructionConstructError::InconsistentDriver) }
+        Ok(())
+    }
+
+    /// Whether the operation expects operands. Decoded instructions only carry data when this is true.
+    pub fn has_operands(&self) -> bool {
+        self.extension.presence().is_some()
+    }
+
+    /// Get the number of operands the operation expects.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::Instruction;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::control::Control;
+    ///
+    /// let no_operation = Instruction { extension: Extension::Control(Control::NoOperation), data: None };
+    /// let add = Instruction { extension: Extension::Arithmetic(Arithmetic::Add), data: None };
+    ///
+    /// assert!(!no_operation.has_operands());
+    /// assert_eq!(no_operation.operand_arity(), 0);
+    /// assert!(add.has_operands());
+    /// assert_eq!(add.operand_arity(), 2);
+    /// ```
+    pub fn operand_arity(&self) -> u8 {
+        match self.extension.presence() {
+            Some(OperandsPresence::AllPresent) => 2,
+            Some(OperandsPresence::Static) | Some(OperandsPresence::Dynamic) => 1,
+            None => 0
+        }
+    }
+
+    /// Decode every instruction in a program. The bytes must end exactly where the last instruction ends. Errors are
+    /// wrapped in [InstructionConstructError::At] with the offset of the instruction that failed.
+    pub fn decode_all(bytes: &[u8]) -> Result<Vec<Self>, InstructionConstructError> {
+        let mut offset = 0;
+        let mut instructions = Vec::new();
+
+        while offset < bytes.len() {
+            let (instruction, next) = Self::decode_at(bytes, offset)?;
+            instructions.push(instruction);
+            offset = next;
+        }
+
+        Ok(instructions)
+    }
+
+    /// Decode the instruction starting at an offset into the bytes. The offset of the byte after the instruction is
+    /// returned with the instruction, so it can be passed back in to decode the next instruction. Errors are wrapped in
+    /// [InstructionConstructError::At] with the offset.
+    pub fn decode_at(bytes: &[u8], offset: usize) -> Result<(Self, usize), InstructionConstructError> {
+        let mut stream = Cursor::new(bytes);
+        stream.set_position(offset as u64);
+
+        match Self::new(&mut stream) {
+            Ok(instruction) => Ok((instruction, stream.position() as usize)),
+            Err(error) => Err(InstructionConstructError::At { offset, error: Box::new(error) })
+        }
+    }
+
+    /// Decode every instruction in a program for an objdump style listing. Each entry is the offset of the instruction,
+    /// the bytes it was decoded from and its assembly as written by the [Display] implementation. Errors are the same
+    /// as with [Instruction::decode_all].
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::control::Control;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number;
+    ///
+    /// let add = Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Add),
+    ///     data: Some(Data {
+    ///         width: number::Size::Word,
+    ///         destination: Destination::Dynamic,
+    ///         synchronous: false,
+    ///         operands: Operands::all(1, Dynamic::Memory(number::Data::Word(0x100)))
+    ///     })
+    /// };
+    ///
+    /// let no_operation = Instruction { extension: Extension::Control(Control::NoOperation), data: None };
+    ///
+    /// let mut bytes = Vec::new();
+    /// Instruction::encode_all(&[ add.clone(), no_operation.clone() ], &mut bytes).unwrap();
+    ///
+    /// let listing = Instruction::disassemble_annotated(&bytes).unwrap();
+    /// let length = add.encode().len();
+    ///
+    /// assert_eq!(listing.len(), 2);
+    /// assert_eq!(listing[0], (0, bytes[..length].to_vec(), "add.w [0x100], r1".to_string()));
+    /// assert_eq!(listing[1], (length as u64, no_operation.encode(), "nop".to_string()));
+    /// ```
+    pub fn disassemble_annotated(bytes: &[u8]) -> Result<Vec<(u64, Vec<u8>, String)>, InstructionConstructError> {
+        let mut offset = 0;
+        let mut listing = Vec::new();
+
+        while offset < bytes.len() {
+            let (instruction, next) = Self::decode_at(bytes, offset)?;
+            listing.push((offset as u64, bytes[offset..next].to_vec(), instruction.to_string()));
+            offset = next;
+        }
+
+        Ok(listing)
+    }
+
+    /// Move a program that was assembled for one base address to another by adding the difference between them to
+    /// every absolute memory address. Register relative operands and constants are left as they are. Immediates keep
+    /// their width so the encoded length of the program does not change. Every address is checked before any are
+    /// changed.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction, RelocateError};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Offset, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number;
+    ///
+    /// let instruction = |x_dynamic| Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Add),
+    ///     data: Some(Data {
+    ///         width: number::Size::Quad,
+    ///         destination: Destination::Static,
+    ///         synchronous: false,
+    ///         operands: Operands::all(0, x_dynamic)
+    ///     })
+    /// };
+    ///
+    /// let offset = Dynamic::Offset(Offset { register: 1, offset: number::Data::Byte(8) });
+    /// let mut program = vec![ instruction(Dynamic::Memory(number::Data::Word(0x100))), instruction(offset.clone()) ];
+    ///
+    /// Instruction::relocate(&mut program, 0x40).unwrap();
+    /// assert_eq!(program[0], instruction(Dynamic::Memory(number::Data::Word(0x140))));
+    /// assert_eq!(program[1], instruction(offset));
+    ///
+    /// assert_eq!(Instruction::relocate(&mut program, -0x141), Err(RelocateError::Underflow(0)));
+    /// assert_eq!(Instruction::relocate(&mut program, 0x10000), Err(RelocateError::Overflow(0)));
+    /// assert_eq!(program[0], instruction(Dynamic::Memory(number::Data::Word(0x140))));
+    /// ```
+    pub fn relocate(instructions: &mut [Self], delta: i64) -> Result<(), RelocateError> {
+        // Find every new address first so nothing is changed on error.
+        let mut relocated = Vec::new();
+        for (index, instruction) in instructions.iter_mut().enumerate() {
+            let address = match instruction.absolute_address() {
+                Some(address) => address,
+                None => continue
             };
 
-            // Construction
-            return Ok(Self {
-                extension,
-                data
-            })
+            let moved = match address.quad().checked_add_signed(delta) {
+                Some(moved) => moved,
+                None => return Err(if delta < 0 { RelocateError::Underflow(index) } else { RelocateError::Overflow(index) })
+            };
+
+            // Unwrapping is safe because the exponent comes from existing data.
+            let fitted = number::Data::from_exponent_selecting(address.clone().exponent(), moved).unwrap();
+            if fitted.quad() != moved { return Err(RelocateError::Overflow(index)) }
+
+            relocated.push((index, fitted));
+        }
+
+        for (index, address) in relocated {
+            // Unwrapping is safe because only instructions with absolute addresses were relocated.
+            *instructions[index].absolute_address().unwrap() = address;
+        }
+
+        Ok(())
+    }
+
+    /// Get the immediate of the dynamic operand if it is an absolute memory address.
+    fn absolute_address(&mut self) -> Option<&mut number::Data> {
+        match &mut self.data.as_mut()?.operands {
+            Operands::AllPresent(AllPresent { x_dynamic: Dynamic::Memory(address), .. }) => Some(address),
+            Operands::Dynamic(Dynamic::Memory(address)) => Some(address),
+            _ => None
         }
-        
-        Ok(Self {
-            extension,
-            data: None
-        })
+    }
+
+    /// Encode every instruction in a program one after another and return the number of bytes written. This is the
+    /// inverse of [Instruction::decode_all].
+    pub fn encode_all(instructions: &[Self], output: &mut impl Write) -> io::Result<usize> {
+        let mut length = 0;
+
+        for instruction in instructions {
+            let encoded = instruction.encode();
+            output.write_all(&encoded)?;
+            length += encoded.len();
+        }
+
+        Ok(length)
+    }
+
+    /// Re-encode every instruction of a program with the narrowest immediates that hold their values and return the
+    /// number of bytes saved. Each instruction is shrunk with [Instruction::normalize] so the meaning of every operand
+    /// is kept. Instructions only refer to absolute addresses and register offsets, there are no relative branches
+    /// whose reach could be cut short by moving the instructions that follow.
+    pub fn optimize_widths(instructions: &mut [Self]) -> usize {
+        instructions.iter_mut().map(|instruction| {
+            let length = instruction.encode().len();
+            instruction.normalize();
+            length - instruction.encode().len()
+        }).sum()
+    }
+
+    /// Decode the instruction which starts at an address of a source. This is the same as [Instruction::new] but the
+    /// instruction is read straight from the source.
+    pub fn from_source(source: &mut impl InstructionSource, address: u64) -> Result<Self, InstructionConstructError> {
+        Self::new(&mut SourceStream { source, address })
     }
 
     /// ```
@@ -677,7 +1135,7 @@ impl Instruction {
     ///
     /// assert_eq!(Instruction::encode_driver_registers_immediate(&mut driver, Some(&registers), Some(&number::Data::Byte(10))).unwrap(), target);
     /// ```
-    pub fn encode(&mut self) -> Vec<u8> {
+    pub fn encode(&self) -> Vec<u8> {
         let mut synchronise = false;
         let mut dynamic_destination = false;
         let mut addressing = 0;
@@ -710,7 +1168,7 @@ impl Instruction {
 
         let mut driver = Driver {
             extension: self.extension.code(),
-            operation: self.extension.operation().code(),
+            operation: self.extension.operation_code(),
             synchronise,
             dynamic_destination,
             addressing,
@@ -726,10 +1184,48 @@ impl Instruction {
         } else { Instruction::encode_driver_registers_immediate(&mut driver, None, None).unwrap() }
     }
 
+    /// Shrink the immediate of the dynamic operand to its smallest width with [Dynamic::normalize]. Instructions that
+    /// mean the same thing but were encoded with different immediate widths encode identically after normalizing.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number;
+    ///
+    /// let add = |immediate: number::Data| Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Add),
+    ///     data: Some(Data {
+    ///         width: number::Size::Byte,
+    ///         destination: Destination::Static,
+    ///         synchronous: false,
+    ///         operands: Operands::all(0, Dynamic::Constant(immediate))
+    ///     })
+    /// };
+    ///
+    /// let mut byte = add(number::Data::Byte(10));
+    /// let mut quad = add(number::Data::Quad(10));
+    /// assert_ne!(byte.encode(), quad.encode());
+    ///
+    /// byte.normalize();
+    /// quad.normalize();
+    /// assert_eq!(byte.encode(), quad.encode());
+    /// assert_eq!(quad.data.unwrap().operands.x_dynamic(), Some(&Dynamic::Constant(number::Data::Byte(10))));
+    /// ```
+    pub fn normalize(&mut self) {
+        if let Some(data) = &mut self.data {
+            match &mut data.operands {
+                Operands::AllPresent(x_all) => x_all.x_dynamic.normalize(),
+                Operands::Dynamic(x_dynamic) => x_dynamic.normalize(),
+                Operands::Static(_) => ()
+            }
+        }
+    }
+
     /// Get the operand that the destination property corresponds to.
     /// ```
     /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction, DestinationError};
-    /// use atln_processor::emulator::processor::processor::instruction::operand::{AllPresent, Dynamic, Operands, Operand, Destination};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Dynamic, Operands, Operand, Destination};
     /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
     /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
     /// use atln_processor::number;
@@ -740,10 +1236,7 @@ impl Instruction {
     ///         width: number::Size::Byte,
     ///         destination: Destination::Static,
     ///         synchronous: false,
-    ///         operands: Operands::AllPresent(AllPresent {
-    ///             x_static: 0,
-    ///             x_dynamic: Dynamic::Register(1)
-    ///         })
+    ///         operands: Operands::all(0, Dynamic::Register(1))
     ///     })
     /// };
     ///
@@ -753,10 +1246,7 @@ impl Instruction {
     ///         width: number::Size::Byte,
     ///         destination: Destination::Dynamic,
     ///         synchronous: false,
-    ///         operands: Operands::AllPresent(AllPresent {
-    ///             x_static: 0,
-    ///             x_dynamic: Dynamic::Register(1)
-    ///         })
+    ///         operands: Operands::all(0, Dynamic::Register(1))
     ///     })
     /// };
     ///
@@ -770,20 +1260,529 @@ impl Instruction {
     /// assert!(matches!(no_operands.destination(), Err(DestinationError::Data)));
     /// ```
     pub fn destination(&self) -> Result<Operand, DestinationError> {
+        self.destination_ref().map(Operand::from)
+    }
+
+    /// Find the memory frames the instruction will read and write if executed in the context. Only the dynamic operand
+    /// can address memory. Operations that take the value of their operands read its frame, and it is also written to
+    /// if it is the destination. [Arithmetic::LoadEffectiveAddress] only takes the address of its dynamic operand, so
+    /// it has no memory effects. Operands in registers and constants have no memory effects either. If a register code
+    /// is out of bounds then the error from [Dynamic::resolve] is returned.
+    pub fn memory_effects(&self, context: &Context) -> Result<MemoryEffects, RegisterError> {
+        let mut effects = MemoryEffects::default();
+        if let Extension::Arithmetic(Arithmetic::LoadEffectiveAddress) = self.extension { return Ok(effects) }
+
+        let data = match &self.data {
+            Some(data) => data,
+            None => return Ok(effects)
+        };
+
+        let x_dynamic = match data.operands.x_dynamic() {
+            Some(x_dynamic) => x_dynamic,
+            None => return Ok(effects)
+        };
+
+        if let ResolvedOperand::Memory(frame) = x_dynamic.resolve(context, data.width.clone())? {
+            if let Destination::Dynamic = data.destination { effects.writes.push(frame.clone()) }
+            effects.reads.push(frame);
+        }
+
+        Ok(effects)
+    }
+
+    /// Same as [Instruction::destination] but the dynamic operand is borrowed from the instruction rather than cloned.
+    pub fn destination_ref(&self) -> Result<OperandRef<'_>, DestinationError> {
         let data = match &self.data {
             Some(data) => data,
             None => return Err(DestinationError::Data)
         };
 
-        Ok(match data.destination {
-            Destination::Static => match data.operands.x_static() {
-                Some(x_static) => Operand::Static(x_static),
-                None => return Err(DestinationError::Static)
+        data.operands.destination_ref(&data.destination)
+    }
+}
+
+impl Display for Instruction {
+    /// Render the instruction as it would be written in assembly. The mnemonic is suffixed with the width, synchronous
+    /// instructions are prefixed with `sync`, and the destination operand is written first. Operands are written the
+    /// same way as [Dynamic] operands, with the static operand being a register.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number;
+    ///
+    /// let mut instruction = Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Subtract),
+    ///     data: Some(Data {
+    ///         width: number::Size::Quad,
+    ///         destination: Destination::Static,
+    ///         synchronous: false,
+    ///         operands: Operands::all(2, Dynamic::Constant(number::Data::Byte(10)))
+    ///     })
+    /// };
+    ///
+    /// assert_eq!(instruction.to_string(), "sub.q r2, 0xa");
+    ///
+    /// let data = instruction.data.as_mut().unwrap();
+    /// data.destination = Destination::Dynamic;
+    /// data.synchronous = true;
+    /// data.operands = Operands::all(2, Dynamic::Memory(number::Data::Byte(10)));
+    /// assert_eq!(instruction.to_string(), "sync sub.q [0xa], r2");
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let data = match &self.data {
+            Some(data) => data,
+            None => return write!(f, "{}", self.extension.mnemonic())
+        };
+
+        let width = match data.width {
+            number::Size::Byte => "b",
+            number::Size::Word => "w",
+            number::Size::Dual => "d",
+            number::Size::Quad => "q"
+        };
+
+        if data.synchronous { write!(f, "sync ")? }
+        write!(f, "{}.{}", self.extension.mnemonic(), width)?;
+
+        // The static operand is a register code, so it is written the same way as a register operand.
+        match &data.operands {
+            Operands::AllPresent(x_all) => {
+                let x_static = Dynamic::Register(x_all.x_static);
+                match data.destination {
+                    Destination::Static => write!(f, " {}, {}", x_static, x_all.x_dynamic),
+                    Destination::Dynamic => write!(f, " {}, {}", x_all.x_dynamic, x_static)
+                }
             },
-            Destination::Dynamic => match data.operands.x_dynamic() {
-                Some(x_dynamic) => Operand::Dynamic(x_dynamic.clone()),
-                None => return Err(DestinationError::Dynamic)
+            Operands::Static(x_static) => write!(f, " {}", Dynamic::Register(*x_static)),
+            Operands::Dynamic(x_dynamic) => write!(f, " {}", x_dynamic)
+        }
+    }
+}
+
+#[cfg(test)]
+mod decode_test {
+    use std::io::Cursor;
+    use crate::emulator::memory::{Frame, GetError, Memory, MemoryCursor};
+    use crate::emulator::processor::processor::instruction::{Data, DataConstructError, DecodeOptions, Driver, DRIVER0_DYNAMIC_DESTINATION, DRIVER0_SYNCHRONISE_MASK, Instruction, InstructionConstructError, MemoryEffects};
+    use crate::emulator::processor::processor::instruction::operand::{CONSTANT_ADDRESSING, Destination, Dynamic, MEMORY_ADDRESSING, Offset, OFFSET_ADDRESSING, Operand, OperandRef, Operands, OperandsPresence, REGISTER_ADDRESSING};
+    use crate::emulator::processor::processor::{Context, Core};
+    use crate::emulator::processor::processor::instruction::operation::{Extension, NO_OPERATION_ENCODED};
+    use crate::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    use crate::emulator::processor::processor::instruction::operation::control::Control;
+    use crate::number;
+
+    /// Create an arithmetic instruction with both operands that is not synchronous.
+    fn all_present(operation: Arithmetic, width: number::Size, destination: Destination, x_static: u8, x_dynamic: Dynamic) -> Instruction {
+        Instruction {
+            extension: Extension::Arithmetic(operation),
+            data: Some(Data { width, destination, synchronous: false, operands: Operands::all(x_static, x_dynamic) })
+        }
+    }
+
+    /// Create a dynamic operand for every addressing mode with an immediate of the given exponent.
+    fn dynamic(addressing: u8, exponent: u8) -> Dynamic {
+        // Fill every byte of the immediate so that truncation would be noticed.
+        let immediate = number::Data::from_exponent_selecting(exponent, 0x0807060504030201).unwrap();
+
+        match addressing {
+            REGISTER_ADDRESSING => Dynamic::Register(5),
+            OFFSET_ADDRESSING => Dynamic::Offset(Offset { register: 6, offset: immediate }),
+            CONSTANT_ADDRESSING => Dynamic::Constant(immediate),
+            MEMORY_ADDRESSING => Dynamic::Memory(immediate),
+            _ => unreachable!()
+        }
+    }
+
+    #[test]
+    fn addressing_matrix() {
+        for addressing in [REGISTER_ADDRESSING, OFFSET_ADDRESSING, CONSTANT_ADDRESSING, MEMORY_ADDRESSING] {
+            for exponent in 0..4 {
+                for width in number::Size::ALL {
+                    let x_dynamic = dynamic(addressing, exponent);
+                    let instruction = all_present(Arithmetic::Subtract, width.clone(), Destination::Static, 3, x_dynamic.clone());
+
+                    let encoded = instruction.encode();
+                    let decoded = Instruction::new(&mut Cursor::new(encoded.clone())).unwrap();
+                    let context = format!("addressing {addressing}, exponent {exponent}, width {width:?}");
+
+                    assert_eq!(decoded.extension, instruction.extension, "{context}");
+                    assert_eq!(decoded.data, instruction.data, "{context}");
+                    assert_eq!(decoded.data.as_ref().unwrap().operands.x_dynamic().unwrap().addressing(), addressing, "{context}");
+
+                    // Data compares by value, so also compare the encoding to catch immediates of the wrong width.
+                    assert_eq!(decoded.encode(), encoded, "{context}");
+                }
             }
-        })
+        }
+    }
+
+    /// Xorshift generator so random instructions are the same on every run without depending on a crate.
+    struct Generator(u64);
+
+    impl Generator {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: u64) -> u64 {
+            self.next() % bound
+        }
+
+        fn immediate(&mut self) -> number::Data {
+            let exponent = self.below(4) as u8;
+            number::Data::from_exponent_selecting(exponent, self.next()).unwrap()
+        }
+
+        fn dynamic(&mut self) -> Dynamic {
+            match self.below(4) as u8 {
+                REGISTER_ADDRESSING => Dynamic::Register(self.below(8) as u8),
+                OFFSET_ADDRESSING => Dynamic::Offset(Offset { register: self.below(8) as u8, offset: self.immediate() }),
+                CONSTANT_ADDRESSING => Dynamic::Constant(self.immediate()),
+                _ => Dynamic::Memory(self.immediate())
+            }
+        }
+
+        fn instruction(&mut self) -> Instruction {
+            let codes = Extension::valid_codes().collect::<Vec<_>>();
+            let (extension_code, operation_code) = codes[self.below(codes.len() as u64) as usize];
+            let extension = Extension::from_codes(extension_code, operation_code).unwrap();
+
+            let operands = match extension.presence() {
+                Some(OperandsPresence::AllPresent) => Operands::all(self.below(8) as u8, self.dynamic()),
+                Some(OperandsPresence::Static) => Operands::Static(self.below(8) as u8),
+                Some(OperandsPresence::Dynamic) => Operands::Dynamic(self.dynamic()),
+                None => return Instruction { extension, data: None }
+            };
+
+            // A constant cannot be the destination.
+            let destination = match operands.x_dynamic() {
+                Some(Dynamic::Constant(_)) | None => Destination::Static,
+                Some(_) if self.below(2) == 0 => Destination::Static,
+                Some(_) => Destination::Dynamic
+            };
+
+            // Synchronous instructions cannot use register addressing.
+            let synchronous = !matches!(operands.x_dynamic(), Some(Dynamic::Register(_))) && self.below(2) == 0;
+
+            Instruction {
+                extension,
+                data: Some(Data {
+                    width: number::Size::from_exponent(self.below(4) as u8).unwrap(),
+                    destination,
+                    synchronous,
+                    operands
+                })
+            }
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn optimize_widths() {
+        let instruction = |x_dynamic| all_present(Arithmetic::Add, number::Size::Quad, Destination::Static, 0, x_dynamic);
+
+        let original = vec![
+            instruction(Dynamic::Constant(number::Data::Quad(10))),
+            instruction(Dynamic::Memory(number::Data::Dual(0x100))),
+            instruction(Dynamic::Offset(Offset { register: 1, offset: number::Data::Word(8) })),
+            instruction(Dynamic::Register(2)),
+            instruction(Dynamic::Constant(number::Data::Quad(u64::MAX)))
+        ];
+
+        let mut program = original.clone();
+        let mut before = Vec::new();
+        let before_length = Instruction::encode_all(&program, &mut before).unwrap();
+
+        let saved = Instruction::optimize_widths(&mut program);
+        let mut after = Vec::new();
+        let after_length = Instruction::encode_all(&program, &mut after).unwrap();
+
+        assert_eq!(saved, 7 + 2 + 1);
+        assert_eq!(before_length - after_length, saved);
+
+        let decoded = Instruction::decode_all(&after).unwrap();
+        assert_eq!(decoded, original);
+        assert_eq!(decoded, program);
+
+        // Shrinking again has nothing left to save.
+        assert_eq!(Instruction::optimize_widths(&mut program), 0);
+    }
+
+    #[test]
+    fn random_round_trip() {
+        let mut generator = Generator(0x2545_F491_4F6C_DD1D);
+
+        for index in 0..10_000 {
+            let instruction = generator.instruction();
+            let encoded = instruction.encode();
+            let decoded = Instruction::new(&mut Cursor::new(encoded.clone())).unwrap();
+
+            assert_eq!(decoded, instruction, "instruction {index}");
+
+            // Data compares by value, so also compare the encoding to catch immediates of the wrong width.
+            assert_eq!(decoded.encode(), encoded, "instruction {index}");
+        }
+    }
+
+    #[test]
+    fn encode_all() {
+        let mut program = Vec::new();
+
+        for addressing in [REGISTER_ADDRESSING, OFFSET_ADDRESSING, CONSTANT_ADDRESSING, MEMORY_ADDRESSING] {
+            for exponent in 0..4 {
+                let width = number::Size::from_exponent(exponent).unwrap();
+                program.push(all_present(Arithmetic::Add, width, Destination::Static, 4, dynamic(addressing, exponent)));
+            }
+        }
+
+        let mut encoded = Vec::new();
+        let length = Instruction::encode_all(&program, &mut encoded).unwrap();
+
+        assert_eq!(length, encoded.len());
+        assert_eq!(Instruction::decode_all(&encoded).unwrap(), program);
+        assert!(Instruction::decode_all(&encoded[0..encoded.len() - 1]).is_err());
+
+        // The second instruction has an operation code that does not exist.
+        let first = program[0].encode();
+        let mut malformed = [first.clone(), vec![0b000000_0_0, 0b1111_00_00]].concat();
+        malformed.extend(program[1].encode());
+
+        match Instruction::decode_all(&malformed) {
+            Err(InstructionConstructError::At { offset, error }) => {
+                assert_eq!(offset, first.len());
+                assert!(matches!(*error, InstructionConstructError::InvalidCode(_)));
+            },
+            result => panic!("Expected an error at the second instruction, got {:?}", result)
+        }
+    }
+
+    #[test]
+    fn no_operation() {
+        let padding = NO_OPERATION_ENCODED.repeat(3);
+        let mut memory = Memory::from(padding.clone());
+        let mut core = Core::default();
+
+        // Each no operation is only its driver bytes.
+        let instructions = Instruction::decode_all(&padding).unwrap();
+        assert_eq!(instructions.len(), 3);
+
+        for instruction in instructions {
+            assert_eq!(instruction, Instruction { extension: Extension::Control(Control::NoOperation), data: None });
+            assert_eq!(instruction.encode(), NO_OPERATION_ENCODED);
+        }
+
+        // Running them only moves the instruction pointer past all of them.
+        for _ in 0..3 { core.step(&mut memory).unwrap(); }
+
+        let mut expected = Core::default();
+        expected.context.registers.instruction_pointer = (3 * NO_OPERATION_ENCODED.len()) as u64;
+        expected.cycles = 3 * expected.cost_model.cost(0);
+
+        assert_eq!(core, expected);
+        assert_eq!(memory.bytes, padding);
+    }
+
+    #[test]
+    fn static_only_width() {
+        let driver = |dynamic_destination: bool| Driver {
+            extension: 0,
+            operation: 0,
+            synchronise: false,
+            dynamic_destination,
+            addressing: 0,
+            immediate_exponent: 0
+        };
+
+        // Dual width and static register 3, followed by a byte that is not part of the data.
+        let mut stream = Cursor::new([0b10__011_000, 0xFF]);
+        let data = Data::new(&mut stream, &OperandsPresence::Static, &driver(false)).unwrap();
+
+        assert_eq!(data.width, number::Size::Dual);
+        assert_eq!(data.operands, Operands::Static(3));
+        assert_eq!(stream.position(), 1);
+
+        // There is no dynamic operand to store the result in.
+        let result = Data::new(&mut Cursor::new([0b10__011_000]), &OperandsPresence::Static, &driver(true));
+        assert!(matches!(result, Err(DataConstructError::Destination)));
+    }
+
+    #[test]
+    fn memory_effects() {
+        let mut context = Context::default();
+        context.registers.general[3] = 0x100;
+
+        let instruction = |destination: Destination, x_dynamic: Dynamic| all_present(Arithmetic::Add, number::Size::Dual, destination, 0, x_dynamic);
+
+        // Adding into memory reads and writes the same frame.
+        let offset = Dynamic::Offset(Offset { register: 3, offset: number::Data::Byte(8) });
+        let frame = Frame { address: 0x108, size: number::Size::Dual };
+        let effects = instruction(Destination::Dynamic, offset.clone()).memory_effects(&context).unwrap();
+
+        assert_eq!(effects, MemoryEffects { reads: vec![frame.clone()], writes: vec![frame.clone()] });
+
+        // The memory operand is only read when the result goes to the register.
+        let effects = instruction(Destination::Static, offset).memory_effects(&context).unwrap();
+        assert_eq!(effects, MemoryEffects { reads: vec![frame], writes: Vec::new() });
+
+        // Registers and operations without operands do not touch memory.
+        let effects = instruction(Destination::Dynamic, Dynamic::Register(1)).memory_effects(&context).unwrap();
+        assert_eq!(effects, MemoryEffects::default());
+
+        let no_operation = Instruction { extension: Extension::Control(Control::NoOperation), data: None };
+        assert_eq!(no_operation.memory_effects(&context).unwrap(), MemoryEffects::default());
+
+        // Loading an effective address does not access the memory it addresses.
+        let lea = all_present(Arithmetic::LoadEffectiveAddress, number::Size::Quad, Destination::Static, 1, Dynamic::Memory(number::Data::Word(0x100)));
+        assert_eq!(lea.memory_effects(&context).unwrap(), MemoryEffects::default());
+    }
+
+    #[test]
+    fn decode_into() {
+        let add = all_present(Arithmetic::Add, number::Size::Word, Destination::Dynamic, 1, Dynamic::Register(2));
+
+        let mut stream = Cursor::new([add.encode(), NO_OPERATION_ENCODED.to_vec()].concat());
+        let mut instruction = Instruction::default();
+
+        instruction.decode_into(&mut stream).unwrap();
+        assert_eq!(instruction, add);
+
+        // The data of the previous instruction does not remain.
+        instruction.decode_into(&mut stream).unwrap();
+        assert_eq!(instruction, Instruction { extension: Extension::Control(Control::NoOperation), data: None });
+
+        // The stream is empty so decoding fails and the instruction is kept.
+        assert!(matches!(instruction.decode_into(&mut stream), Err(InstructionConstructError::Length)));
+        assert_eq!(instruction.extension, Extension::Control(Control::NoOperation));
+    }
+
+    #[test]
+    fn immediate_too_wide() {
+        let instruction = all_present(Arithmetic::Add, number::Size::Quad, Destination::Static, 0, Dynamic::Constant(number::Data::Quad(u64::MAX)));
+
+        let encoded = instruction.encode();
+        let dual = DecodeOptions { max_immediate_size: number::Size::Dual };
+
+        assert!(matches!(Instruction::new_with(&mut Cursor::new(&encoded), &dual), Err(InstructionConstructError::ImmediateTooWide)));
+        assert_eq!(Instruction::new_with(&mut Cursor::new(&encoded), &DecodeOptions::default()).unwrap(), instruction);
+
+        // Register addressing has no immediate, so the cap does not apply.
+        let register = all_present(Arithmetic::Add, number::Size::Quad, Destination::Static, 0, Dynamic::Register(1));
+
+        let byte = DecodeOptions { max_immediate_size: number::Size::Byte };
+        assert_eq!(Instruction::new_with(&mut Cursor::new(register.encode()), &byte).unwrap(), register);
+    }
+
+    #[test]
+    fn destination_ref() {
+        let instruction = all_present(Arithmetic::Add, number::Size::Word, Destination::Dynamic, 2, Dynamic::Memory(number::Data::Word(0x400)));
+
+        let x_dynamic = match instruction.destination_ref().unwrap() {
+            OperandRef::Dynamic(x_dynamic) => x_dynamic,
+            OperandRef::Static(_) => panic!("Expected the dynamic operand")
+        };
+
+        // The operand is the one stored in the instruction, not a copy of it.
+        let stored = instruction.data.as_ref().unwrap().operands.x_dynamic().unwrap();
+        assert!(std::ptr::eq(x_dynamic, stored));
+        assert_eq!(instruction.destination().unwrap(), Operand::Dynamic(x_dynamic.clone()));
+    }
+
+    #[test]
+    fn driver0_flags() {
+        for (synchronous, destination) in [(false, Destination::Static), (true, Destination::Static), (false, Destination::Dynamic), (true, Destination::Dynamic)] {
+            let mut instruction = all_present(Arithmetic::Add, number::Size::Word, destination.clone(), 3, dynamic(MEMORY_ADDRESSING, 1));
+            instruction.data.as_mut().unwrap().synchronous = synchronous;
+
+            let encoded = instruction.encode();
+            assert_eq!(encoded[0] & DRIVER0_SYNCHRONISE_MASK != 0, synchronous);
+            assert_eq!(encoded[0] & DRIVER0_DYNAMIC_DESTINATION != 0, destination == Destination::Dynamic);
+
+            let data = Instruction::new(&mut Cursor::new(encoded)).unwrap().data.unwrap();
+            assert_eq!(data.synchronous, synchronous);
+            assert_eq!(data.destination, destination);
+        }
+    }
+
+    #[test]
+    fn inconsistent_driver() {
+        // Addressing bits set on an operation without operands.
+        let addressing = [NO_OPERATION_ENCODED[0], NO_OPERATION_ENCODED[1] | 0b01_00];
+        // Dynamic destination bit set on an operation without operands.
+        let destination = [NO_OPERATION_ENCODED[0] | 0b1, NO_OPERATION_ENCODED[1]];
+
+        // Both decoders reject the same driver bytes.
+        for driver in [addressing, destination] {
+            assert!(matches!(Instruction::new(&mut Cursor::new(driver)), Err(InstructionConstructError::InconsistentDriver)));
+            assert!(matches!(Instruction::decode_header(&mut Cursor::new(driver)), Err(InstructionConstructError::InconsistentDriver)));
+        }
+    }
+
+    #[test]
+    fn decode_at() {
+        let first = all_present(Arithmetic::Add, number::Size::Quad, Destination::Static, 1, dynamic(CONSTANT_ADDRESSING, 2));
+
+        let second = all_present(Arithmetic::Subtract, number::Size::Byte, Destination::Dynamic, 2, dynamic(OFFSET_ADDRESSING, 1));
+
+        let mut encoded = first.encode();
+        let offset = encoded.len();
+        encoded.extend(second.encode());
+
+        assert_eq!(Instruction::decode_at(&encoded, 0).unwrap(), (first, offset));
+        assert_eq!(Instruction::decode_at(&encoded, offset).unwrap(), (second, encoded.len()));
+        assert!(matches!(Instruction::decode_at(&encoded, encoded.len()), Err(InstructionConstructError::At { offset, .. }) if offset == encoded.len()));
+    }
+
+    #[test]
+    fn decode_header() {
+        for addressing in [REGISTER_ADDRESSING, OFFSET_ADDRESSING, CONSTANT_ADDRESSING, MEMORY_ADDRESSING] {
+            for exponent in 0..4 {
+                let instruction = all_present(Arithmetic::Add, number::Size::Byte, Destination::Static, 1, dynamic(addressing, exponent));
+
+                let encoded = instruction.encode();
+                let (extension, has_operands, length) = Instruction::decode_header(&mut Cursor::new(encoded.clone())).unwrap();
+
+                assert_eq!(extension, instruction.extension);
+                assert!(has_operands);
+                assert_eq!(length as usize, encoded.len(), "addressing {addressing}, exponent {exponent}");
+            }
+        }
+    }
+
+    #[test]
+    fn from_source() {
+        let mut instruction = all_present(Arithmetic::Add, number::Size::Word, Destination::Dynamic, 2, dynamic(MEMORY_ADDRESSING, 1));
+        instruction.data.as_mut().unwrap().synchronous = true;
+
+        let encoded = instruction.encode();
+        let mut bytes = vec![0u8; 3];
+        bytes.extend(encoded.clone());
+
+        // Physical memory.
+        let mut memory = Memory::from(bytes.clone());
+        let decoded = Instruction::from_source(&mut memory, 3).unwrap();
+        assert_eq!(decoded.extension, instruction.extension);
+        assert_eq!(decoded.data, instruction.data);
+
+        // Translated memory with 4 byte pages, so the instruction crosses pages.
+        let mut memory = Memory::from(bytes.clone());
+        memory.page_item_bits = 2;
+        memory.pages.insert(4, 0);
+        memory.pages.insert(5, 1);
+        let mut cursor = MemoryCursor::from(&mut memory);
+        cursor.translate = true;
+        let decoded = Instruction::from_source(&mut cursor, 0x13).unwrap();
+        assert_eq!(decoded.data, instruction.data);
+
+        // Only the first byte is mapped.
+        cursor.memory.pages.remove(&5);
+        assert!(Instruction::from_source(&mut cursor, 0x13).is_err());
+        assert_eq!(cursor.get_error, Some(GetError::PageFault { address: 0x14 }));
+
+        let decoded = Instruction::from_source(&mut Cursor::new(bytes), 3).unwrap();
+        assert_eq!(decoded.data, instruction.data);
+    }
+}
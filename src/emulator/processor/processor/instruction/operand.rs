@@ -2,11 +2,15 @@
 //! 
 //! The static operand is a simple and optional register field which can be used as the destination.
 
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::io::Read;
+use crate::emulator::memory::Frame;
+use crate::emulator::processor::processor::{Context, RegisterError, RegisterFile};
 use crate::emulator::processor::processor::instruction::operation::Operation;
-use crate::emulator::processor::processor::instruction::{Driver, Registers};
+use crate::emulator::processor::processor::instruction::{DestinationError, Driver, Registers};
 use crate::{number};
-use crate::number::{BYTE_SIZE, DUAL_SIZE, QUAD_SIZE, WORD_SIZE};
+use crate::number::{BYTE_SIZE, DUAL_SIZE, Endianness, QUAD_SIZE, WORD_SIZE};
 
 // region: Constants
 pub const REGISTER_ADDRESSING    : u8 = 0;
@@ -46,6 +50,17 @@ pub enum Dynamic {
     Memory(number::Data)
 }
 
+/// Where a dynamic operand's value lives once its addressing has been evaluated against an execution context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedOperand {
+    /// The value is in the register with this code.
+    Register(u8),
+    /// The value is in memory at this frame.
+    Memory(Frame),
+    /// The value is the immediate itself and has no location.
+    Constant(number::Data)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ReadImmediateError {
     /// Caused by reading from the stream.
@@ -85,7 +100,37 @@ impl Dynamic {
     /// assert!(matches!(Dynamic::read_immediate(IMMEDIATE_EXPONENT_DUAL, &mut Cursor::new(dual.to_le_bytes())).unwrap(), number::Data::Dual(_dual)));
     /// assert!(matches!(Dynamic::read_immediate(IMMEDIATE_EXPONENT_QUAD, &mut Cursor::new(quad.to_le_bytes())).unwrap(), number::Data::Quad(_quad)));
     /// ```
+    ///
+    /// Immediates are assembled in a buffer the size of the widest variant, so an immediate with more bytes than a
+    /// [number::Data::Quad] holds is rejected before anything is read rather than overflowing.
+    /// ```
+    /// use std::io::Cursor;
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Dynamic, IMMEDIATE_EXPONENT_QUAD, ReadImmediateError};
+    ///
+    /// let mut stream = Cursor::new([0xFFu8; 16]);
+    ///
+    /// assert_eq!(Dynamic::read_immediate(IMMEDIATE_EXPONENT_QUAD + 1, &mut stream).unwrap_err(), ReadImmediateError::Exponent);
+    /// assert_eq!(stream.position(), 0);
+    /// ```
     pub fn read_immediate(exponent: u8, stream: &mut impl Read) -> Result<number::Data, ReadImmediateError> {
+        Self::read_immediate_ordered(exponent, Endianness::Little, stream)
+    }
+
+    /// Same as [Dynamic::read_immediate] but the immediate bytes are assembled in the given byte order.
+    /// ```
+    /// use std::io::Cursor;
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Dynamic, IMMEDIATE_EXPONENT_BYTE, IMMEDIATE_EXPONENT_WORD};
+    /// use atln_processor::number::{Data, Endianness};
+    ///
+    /// let bytes = [0x01, 0x02];
+    ///
+    /// assert!(matches!(Dynamic::read_immediate_ordered(IMMEDIATE_EXPONENT_WORD, Endianness::Little, &mut Cursor::new(bytes)).unwrap(), Data::Word(0x0201)));
+    /// assert!(matches!(Dynamic::read_immediate_ordered(IMMEDIATE_EXPONENT_WORD, Endianness::Big, &mut Cursor::new(bytes)).unwrap(), Data::Word(0x0102)));
+    ///
+    /// // Single bytes have no order.
+    /// assert!(matches!(Dynamic::read_immediate_ordered(IMMEDIATE_EXPONENT_BYTE, Endianness::Big, &mut Cursor::new(bytes)).unwrap(), Data::Byte(0x01)));
+    /// ```
+    pub fn read_immediate_ordered(exponent: u8, endianness: Endianness, stream: &mut impl Read) -> Result<number::Data, ReadImmediateError> {
         let mut quad_buffer = [0u8; QUAD_SIZE as usize];
 
         let buffer: &mut [u8] = match exponent {
@@ -101,6 +146,9 @@ impl Dynamic {
             Err(_) => return Err(ReadImmediateError::Read)
         };
 
+        // The buffer is assembled as little endian, so put big endian bytes in reverse order.
+        if let Endianness::Big = endianness { buffer.reverse() }
+
         // Unwrapping is safe here because the exponent is validated when creating the buffer.
         Ok(number::Data::from_exponent_selecting(exponent, u64::from_le_bytes(quad_buffer)).unwrap())
     }
@@ -155,6 +203,45 @@ impl Dynamic {
         })
     }
 
+    /// Same as [Dynamic::new] but also returns the number of immediate bytes read from the stream. The register
+    /// addressing mode reads no bytes.
+    /// ```
+    /// use std::io::Cursor;
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Dynamic, IMMEDIATE_EXPONENT_DUAL, MEMORY_ADDRESSING, OFFSET_ADDRESSING, REGISTER_ADDRESSING};
+    ///
+    /// let immediate = [1, 2, 3, 4];
+    ///
+    /// assert_eq!(Dynamic::new_counted(1, REGISTER_ADDRESSING, IMMEDIATE_EXPONENT_DUAL, &mut Cursor::new(immediate)).unwrap().1, 0);
+    /// assert_eq!(Dynamic::new_counted(1, OFFSET_ADDRESSING, IMMEDIATE_EXPONENT_DUAL, &mut Cursor::new(immediate)).unwrap().1, immediate.len());
+    /// assert_eq!(Dynamic::new_counted(0, MEMORY_ADDRESSING, IMMEDIATE_EXPONENT_DUAL, &mut Cursor::new(immediate)).unwrap().1, immediate.len());
+    /// ```
+    pub fn new_counted(register: u8, addressing: u8, immediate_exponent: u8, immediate_stream: &mut impl Read) -> Result<(Self, usize), DynamicConstructError> {
+        let operand = Self::new(register, addressing, immediate_exponent, immediate_stream)?;
+
+        // Immediates are always read in full, so the size of the immediate is the number of bytes read.
+        let count = match operand.immediate() {
+            Some(immediate) => immediate.size() as usize,
+            None => 0
+        };
+
+        Ok((operand, count))
+    }
+
+    /// Encode the immediate in the given byte order. [None] is returned if the addressing mode has no immediate.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::operand::Dynamic;
+    /// use atln_processor::number::{Data, Endianness};
+    ///
+    /// let constant = Dynamic::Constant(Data::Word(0x0102));
+    ///
+    /// assert_eq!(constant.encode_immediate(Endianness::Little).unwrap(), vec![0x02, 0x01]);
+    /// assert_eq!(constant.encode_immediate(Endianness::Big).unwrap(), vec![0x01, 0x02]);
+    /// assert_eq!(Dynamic::Register(0).encode_immediate(Endianness::Big), None);
+    /// ```
+    pub fn encode_immediate(&self, endianness: Endianness) -> Option<Vec<u8>> {
+        Some(self.immediate()?.to_bytes(endianness))
+    }
+
     pub fn addressing(&self) -> u8 {
         match self {
             Self::Register(_) => REGISTER_ADDRESSING,
@@ -173,6 +260,66 @@ impl Dynamic {
         })
     }
 
+    /// Shrink the immediate to the smallest data variant that holds its value. Immediates are unsigned and zero
+    /// extended when read, so this never changes what the operand refers to. The register addressing mode has no
+    /// immediate and is left as is.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Dynamic, Offset};
+    /// use atln_processor::number;
+    ///
+    /// let mut constant = Dynamic::Constant(number::Data::Quad(10));
+    /// constant.normalize();
+    /// assert!(matches!(constant, Dynamic::Constant(number::Data::Byte(10))));
+    ///
+    /// let mut offset = Dynamic::Offset(Offset { register: 1, offset: number::Data::Dual(256) });
+    /// offset.normalize();
+    /// assert!(matches!(offset, Dynamic::Offset(Offset { register: 1, offset: number::Data::Word(256) })));
+    /// ```
+    pub fn normalize(&mut self) {
+        match self {
+            Self::Register(_) => (),
+            Self::Offset(offset) => offset.offset = number::Data::from_quad_selecting(offset.offset.quad()),
+            Self::Constant(immediate) | Self::Memory(immediate) => *immediate = number::Data::from_quad_selecting(immediate.quad())
+        }
+    }
+
+    /// Compute where the operand's value is located. Memory addressing modes produce a frame of the given width at the
+    /// effective address. The offset addressing mode adds the offset to the register's value and wraps on overflow.
+    /// If a register code is not smaller than [REGISTERS_COUNT] then [Err(RegisterError::OutOfBounds)] is returned.
+    /// ```
+    /// use atln_processor::emulator::memory::Frame;
+    /// use atln_processor::emulator::processor::processor::Context;
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Dynamic, Offset, ResolvedOperand};
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let mut context = Context::default();
+    /// context.registers.general[2] = 0x1000;
+    ///
+    /// let offset = Dynamic::Offset(Offset { register: 2, offset: Data::Word(0x20) });
+    /// let memory = Dynamic::Memory(Data::Dual(0x400));
+    ///
+    /// assert_eq!(offset.resolve(&context, Size::Dual), Ok(ResolvedOperand::Memory(Frame { address: 0x1020, size: Size::Dual })));
+    /// assert_eq!(memory.resolve(&context, Size::Byte), Ok(ResolvedOperand::Memory(Frame { address: 0x400, size: Size::Byte })));
+    /// assert_eq!(Dynamic::Register(2).resolve(&context, Size::Quad), Ok(ResolvedOperand::Register(2)));
+    /// assert_eq!(Dynamic::Constant(Data::Byte(5)).resolve(&context, Size::Quad), Ok(ResolvedOperand::Constant(Data::Byte(5))));
+    /// ```
+    ///
+    /// [REGISTERS_COUNT]: crate::emulator::processor::processor::REGISTERS_COUNT
+    pub fn resolve(&self, context: &Context, width: number::Size) -> Result<ResolvedOperand, RegisterError> {
+        Ok(match self {
+            Self::Register(register) => {
+                context.register(*register)?;
+                ResolvedOperand::Register(*register)
+            },
+            Self::Offset(offset) => ResolvedOperand::Memory(Frame {
+                address: context.register(offset.register)?.wrapping_add(offset.offset.quad()),
+                size: width
+            }),
+            Self::Constant(constant) => ResolvedOperand::Constant(constant.clone()),
+            Self::Memory(address) => ResolvedOperand::Memory(Frame { address: address.quad(), size: width })
+        })
+    }
+
     /// Get the register code if the addressing includes one. Addressing modes [Register] and [Offset] support this
     /// function and will return an instance of [Some] otherwise [None] will be returned.
     pub fn register(&self) -> Option<u8> {
@@ -184,6 +331,34 @@ impl Dynamic {
     }
 }
 
+impl Display for Dynamic {
+    /// Render the operand as it would be written in assembly. Registers are written with [RegisterFile::mnemonic],
+    /// immediates are written in hexadecimal, and operands that dereference memory are wrapped in brackets.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Dynamic, Offset};
+    /// use atln_processor::number::Data;
+    ///
+    /// assert_eq!(Dynamic::Register(3).to_string(), "r3");
+    /// assert_eq!(Dynamic::Offset(Offset { register: 5, offset: Data::Word(0x10) }).to_string(), "[r5 + 0x10]");
+    /// assert_eq!(Dynamic::Constant(Data::Byte(10)).to_string(), "0xa");
+    /// assert_eq!(Dynamic::Memory(Data::Dual(0x400)).to_string(), "[0x400]");
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Codes without a register still get a name so malformed operands can be displayed.
+        let name = |register: u8| match RegisterFile::mnemonic(register) {
+            Some(mnemonic) => mnemonic.to_string(),
+            None => format!("r{register}")
+        };
+
+        match self {
+            Self::Register(register) => write!(f, "{}", name(*register)),
+            Self::Offset(offset) => write!(f, "[{} + {:#x}]", name(offset.register), offset.offset.quad()),
+            Self::Constant(constant) => write!(f, "{:#x}", constant.quad()),
+            Self::Memory(address) => write!(f, "[{:#x}]", address.quad())
+        }
+    }
+}
+
 /// Operands provide the operation the arguments necessary for computing, There are 2 types of operands, static and 
 /// dynamic operands.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -191,6 +366,22 @@ pub enum Operand {
     Static(Static),
     Dynamic(Dynamic)
 }
+
+/// Same as [Operand] but the dynamic operand is borrowed from the operands it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandRef<'a> {
+    Static(Static),
+    Dynamic(&'a Dynamic)
+}
+
+impl<'a> From<OperandRef<'a>> for Operand {
+    fn from(value: OperandRef<'a>) -> Self {
+        match value {
+            OperandRef::Static(x_static) => Operand::Static(x_static),
+            OperandRef::Dynamic(x_dynamic) => Operand::Dynamic(x_dynamic.clone())
+        }
+    }
+}
 // endregion
 
 // region: Instruction ready operand parameter that contains addressing for a different modes of having operands.
@@ -292,6 +483,45 @@ impl<'a> Operands {
         })
     }
 
+    /// Create operands with both the static and dynamic operand present.
+    pub fn all(x_static: Static, x_dynamic: Dynamic) -> Self {
+        Self::AllPresent(AllPresent { x_static, x_dynamic })
+    }
+
+    /// Create operands with only the static operand present.
+    pub fn static_only(x_static: Static) -> Self {
+        Self::Static(x_static)
+    }
+
+    /// Create operands with only the dynamic operand present.
+    pub fn dynamic_only(x_dynamic: Dynamic) -> Self {
+        Self::Dynamic(x_dynamic)
+    }
+
+    /// Check that the operands present are exactly the ones expected, such as the presence returned by
+    /// [Extension::presence].
+    ///
+    /// [Extension::presence]: crate::emulator::processor::processor::instruction::operation::Extension::presence
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Dynamic, Operands, OperandsPresence};
+    /// use atln_processor::number;
+    ///
+    /// let operands = Operands::dynamic_only(Dynamic::Constant(number::Data::Byte(1)));
+    /// assert!(operands.matches_arity(&OperandsPresence::Dynamic));
+    /// assert!(!operands.matches_arity(&OperandsPresence::AllPresent));
+    /// assert!(!operands.matches_arity(&OperandsPresence::Static));
+    ///
+    /// assert!(Operands::static_only(1).matches_arity(&OperandsPresence::Static));
+    /// assert!(Operands::all(1, Dynamic::Register(2)).matches_arity(&OperandsPresence::AllPresent));
+    /// ```
+    pub fn matches_arity(&self, arity: &OperandsPresence) -> bool {
+        match self {
+            Self::AllPresent(_) => arity.expects_all(),
+            Self::Static(_) => arity.expects_only_static(),
+            Self::Dynamic(_) => arity.expects_only_dynamic()
+        }
+    }
+
     /// Try to get the static operand.
     pub fn x_static(&self) -> Option<Static> {
         Some(match self {
@@ -309,6 +539,42 @@ impl<'a> Operands {
             _ => return None
         })
     }
+
+    /// Get the operand selected by a destination. If the selected operand is not present then
+    /// [Err(DestinationError::Static)] or [Err(DestinationError::Dynamic)] is returned.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::DestinationError;
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operand, Operands};
+    ///
+    /// let operands = Operands::Dynamic(Dynamic::Register(1));
+    ///
+    /// assert_eq!(operands.destination(&Destination::Dynamic), Ok(Operand::Dynamic(Dynamic::Register(1))));
+    /// assert_eq!(operands.destination(&Destination::Static), Err(DestinationError::Static));
+    /// ```
+    pub fn destination(&self, destination: &Destination) -> Result<Operand, DestinationError> {
+        self.destination_ref(destination).map(Operand::from)
+    }
+
+    /// Same as [Operands::destination] but the dynamic operand is borrowed rather than cloned.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, OperandRef, Operands};
+    ///
+    /// let operands = Operands::Dynamic(Dynamic::Register(1));
+    ///
+    /// assert_eq!(operands.destination_ref(&Destination::Dynamic), Ok(OperandRef::Dynamic(&Dynamic::Register(1))));
+    /// ```
+    pub fn destination_ref(&self, destination: &Destination) -> Result<OperandRef<'_>, DestinationError> {
+        Ok(match destination {
+            Destination::Static => match self.x_static() {
+                Some(x_static) => OperandRef::Static(x_static),
+                None => return Err(DestinationError::Static)
+            },
+            Destination::Dynamic => match self.x_dynamic() {
+                Some(x_dynamic) => OperandRef::Dynamic(x_dynamic),
+                None => return Err(DestinationError::Dynamic)
+            }
+        })
+    }
 }
 
 impl From<Operands> for OperandsPresence {
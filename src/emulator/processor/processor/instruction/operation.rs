@@ -1,16 +1,23 @@
-use emulator::processor;
-use crate::emulator::processor::processor::instruction;
+use crate::emulator::memory::{GetError, MemoryAccess};
+use crate::emulator::processor::processor::{Core, RegisterError, StoreError};
+use crate::emulator::processor::processor::instruction::Instruction;
 use crate::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+use crate::emulator::processor::processor::instruction::operation::control::Control;
 use crate::utility::Coded;
 
 use super::operand::OperandsPresence;
 
 pub mod arithmetic;
+pub mod control;
 
 // Extension identifier codes
 
 pub const ARITHMETIC_CODE: u8 = 0;
 pub const DATA_CODE      : u8 = 1;
+pub const CONTROL_CODE   : u8 = 2;
+
+/// Encoded no operation instruction. Assemblers can repeat this to pad code.
+pub const NO_OPERATION_ENCODED: [u8; 2] = [ CONTROL_CODE << 2, control::NO_OPERATION_CODE << 4 ];
 
 // Operation
 
@@ -20,14 +27,40 @@ pub enum OperationExecuteError {
     /// whether the data parameter was expected.
     Data(bool),
     /// The operand presence was incorrect. The expected operand presence is contained in this error.
-    Operand(OperandsPresence)
+    Operand(OperandsPresence),
+    /// The dynamic operand uses an addressing mode the operation does not support.
+    Addressing,
+    /// The destination is an operand the operation cannot store its result in.
+    Destination,
+    /// An operand refers to a register that does not exist.
+    Register(RegisterError),
+    /// An operand could not be read from memory.
+    Memory(GetError),
+    /// The result could not be written to the destination.
+    Store(StoreError),
+    /// There is no handler for the category of the operation.
+    Category(Category)
+}
+
+/// The kind of work an operation does. Used to handle groups of operations the same way regardless of which extension
+/// they belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Arithmetic,
+    Logical,
+    ControlFlow,
+    Memory,
+    Io
 }
 
-pub trait Operation<'a>: Coded<u8> + Default {
-    fn execute(&self, code: u8, data: Option<&instruction::Data>, context: &mut processor::processor::Context) -> Result<(), OperationExecuteError>;
+pub trait Operation<'a>: Coded<u8> {
+    fn execute(&self, code: u8, instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> where Self: Sized;
 
     /// Get which operands are expected. [None] indicates that the operation does not expect any operands.
     fn get_presence(&self) -> Option<OperandsPresence>;
+
+    /// Get the kind of work this operation does.
+    fn category(&self) -> Category;
 }
 
 // Extension
@@ -37,6 +70,11 @@ pub trait Operation<'a>: Coded<u8> + Default {
 pub type ExtensionCode = u8;
 pub type OperationCode = u8;
 
+/// Largest extension code that fits in the 6 bit extension field.
+pub const EXTENSION_CODE_MAX: ExtensionCode = 0b111111;
+/// Largest operation code that fits in the 4 bit operation field.
+pub const OPERATION_CODE_MAX: OperationCode = 0b1111;
+
 /// Used to indicate that one of the codes were invalid for the target.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExtensionFromCodeInvalid {
@@ -47,9 +85,10 @@ pub enum ExtensionFromCodeInvalid {
 /// Contains groups of operations which are categorized by extension. This allows for operations to have duplicate
 /// names and also allows for the operation set to extended in the future without breaking code that is already
 /// compiled for the architecture.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Extension {
     Arithmetic(Arithmetic),
+    Control(Control)
 }
 
 impl Default for Extension {
@@ -68,22 +107,126 @@ impl Extension {
                 Some(operation) => operation,
                 None => return invalid_operation
             }),
+            CONTROL_CODE => Self::Control(match Control::from_code(operation) {
+                Some(operation) => operation,
+                None => return invalid_operation
+            }),
             _ => return Err(ExtensionFromCodeInvalid::Extension)
         })
     }
 
+    /// Check whether the extension and operation codes select an operation.
+    pub fn is_valid_code(extension: ExtensionCode, operation: OperationCode) -> bool {
+        Self::from_codes(extension, operation).is_ok()
+    }
+
+    /// Iterate over every pair of extension and operation codes that selects an operation. Codes are checked with
+    /// [Extension::from_codes] so the pairs are always in sync with the decoder.
+    pub fn valid_codes() -> impl Iterator<Item=(ExtensionCode, OperationCode)> {
+        (0..=EXTENSION_CODE_MAX)
+            .flat_map(|extension| (0..=OPERATION_CODE_MAX).map(move |operation| (extension, operation)))
+            .filter(|(extension, operation)| Self::is_valid_code(*extension, *operation))
+    }
+
+    /// Get the assembly name of the contained operation. Names are unique across all extensions.
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::Arithmetic(arithmetic) => arithmetic.mnemonic(),
+            Self::Control(control) => control.mnemonic()
+        }
+    }
+
+    /// Create an extension containing the operation with the assembly name. [None] is returned if no operation in any
+    /// extension has the name.
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        if let Some(arithmetic) = Arithmetic::from_mnemonic(mnemonic) { return Some(Self::Arithmetic(arithmetic)) }
+        if let Some(control) = Control::from_mnemonic(mnemonic) { return Some(Self::Control(control)) }
+        None
+    }
+
     /// Retrieve the underlying operation trait.
-    pub fn operation(&mut self) -> &mut impl Operation {
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::operation::{Category, Extension};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::control::Control;
+    ///
+    /// let extension = Extension::Control(Control::NoOperation);
+    ///
+    /// assert_eq!(extension.operation().category(), Category::ControlFlow);
+    /// assert_eq!(extension.operation().get_presence(), None);
+    /// ```
+    pub fn operation(&self) -> &dyn Operation<'_> {
+        match self {
+            Self::Arithmetic(arithmetic) => arithmetic,
+            Self::Control(control) => control
+        }
+    }
+
+    /// Get the category of the contained operation.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::operation::{Category, Extension};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    ///
+    /// assert_eq!(Extension::Arithmetic(Arithmetic::Subtract).category(), Category::Arithmetic);
+    /// ```
+    pub fn category(&self) -> Category {
+        match self {
+            Self::Arithmetic(arithmetic) => arithmetic.category(),
+            Self::Control(control) => control.category()
+        }
+    }
+
+    /// Get the code of the contained operation within its extension.
+    pub fn operation_code(&self) -> OperationCode {
+        match self {
+            Self::Arithmetic(arithmetic) => arithmetic.code(),
+            Self::Control(control) => control.code()
+        }
+    }
+
+    /// Get which operands the contained operation expects. [None] indicates that the operation does not expect any
+    /// operands.
+    pub fn presence(&self) -> Option<OperandsPresence> {
         match self {
-            Self::Arithmetic(arithmetic) => arithmetic
+            Self::Arithmetic(arithmetic) => arithmetic.get_presence(),
+            Self::Control(control) => control.get_presence()
         }
     }
+
+    /// Run the contained operation as part of executing the instruction. The operation is given to the handler for its
+    /// [Category], so anything that applies to a whole category is done in one place. If no handler exists for the
+    /// category then [Err(OperationExecuteError::Category)] is returned.
+    pub fn execute(&self, instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        match self {
+            Self::Arithmetic(arithmetic) => Self::execute_category(arithmetic, instruction, core, memory),
+            Self::Control(control) => Self::execute_category(control, instruction, core, memory)
+        }
+    }
+
+    /// Give the operation to the handler for its category.
+    fn execute_category<'a>(operation: &impl Operation<'a>, instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        match operation.category() {
+            Category::Arithmetic => Self::execute_arithmetic(operation, instruction, core, memory),
+            Category::ControlFlow => Self::execute_control_flow(operation, instruction, core, memory),
+            category => Err(OperationExecuteError::Category(category))
+        }
+    }
+
+    /// Handler for [Category::Arithmetic] operations.
+    fn execute_arithmetic<'a>(operation: &impl Operation<'a>, instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        operation.execute(operation.code(), instruction, core, memory)
+    }
+
+    /// Handler for [Category::ControlFlow] operations.
+    fn execute_control_flow<'a>(operation: &impl Operation<'a>, instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        operation.execute(operation.code(), instruction, core, memory)
+    }
 }
 
 impl Coded<u8> for Extension {
-    fn code(&mut self) -> u8 {
+    fn code(&self) -> u8 {
         match self {
-            Self::Arithmetic(_) => ARITHMETIC_CODE
+            Self::Arithmetic(_) => ARITHMETIC_CODE,
+            Self::Control(_) => CONTROL_CODE
         }
     }
 }
@@ -91,8 +234,14 @@ impl Coded<u8> for Extension {
 // TODO: Moved to doctest
 #[cfg(test)]
 mod extension_test {
-    use crate::emulator::processor::processor::instruction::operation::{ARITHMETIC_CODE, Coded, Extension, Operation};
+    use crate::emulator::memory::Memory;
+    use crate::emulator::processor::processor::Core;
+    use crate::emulator::processor::processor::instruction::{Data, Instruction};
+    use crate::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    use crate::emulator::processor::processor::instruction::operation::{ARITHMETIC_CODE, Coded, DATA_CODE, Extension, Operation, OperationExecuteError};
     use crate::emulator::processor::processor::instruction::operation::arithmetic::{ADD_CODE, Arithmetic, SUBTRACT_CODE};
+    use crate::emulator::processor::processor::instruction::operation::control::Control;
+    use crate::number;
 
     #[test]
     fn from_codes() {
@@ -104,9 +253,77 @@ mod extension_test {
 
     #[test]
     fn operation() {
-        let mut extension = Extension::from_codes(ARITHMETIC_CODE, ADD_CODE).unwrap();
-        let operation_generic = extension.operation();
+        let extension = Extension::from_codes(ARITHMETIC_CODE, ADD_CODE).unwrap();
+        assert_eq!(extension.presence(), Arithmetic::Add.get_presence());
+        assert_eq!(extension.operation_code(), ADD_CODE);
+
+        // The projection borrows immutably, so it can be taken more than once and always gives the same operation.
+        let first = extension.operation();
+        let second = extension.operation();
+
+        assert_eq!(first.code(), second.code());
+        assert_eq!(first.category(), second.category());
+        assert_eq!(first.get_presence(), second.get_presence());
+        assert_eq!(first.code(), ADD_CODE);
+    }
+
+    #[test]
+    fn execute() {
+        let mut core = Core::default();
+        let mut memory = Memory::default();
+        let data = Data {
+            width: number::Size::Quad,
+            destination: Destination::Static,
+            synchronous: false,
+            operands: Operands::all(1, Dynamic::Memory(number::Data::Word(0x100)))
+        };
+
+        // Each operation reaches the handler for its category.
+        let lea = Instruction { extension: Extension::Arithmetic(Arithmetic::LoadEffectiveAddress), data: Some(data.clone()) };
+        lea.extension.execute(&lea, &mut core, &mut memory).unwrap();
+        assert_eq!(core.context.registers.general[1], 0x100);
+
+        let mut no_operation = Instruction { extension: Extension::Control(Control::NoOperation), data: None };
+        assert_eq!(no_operation.extension.execute(&no_operation, &mut core, &mut memory), Ok(()));
+
+        no_operation.data = Some(data);
+        assert_eq!(no_operation.extension.execute(&no_operation, &mut core, &mut memory), Err(OperationExecuteError::Data(false)));
+    }
+
+    #[test]
+    fn valid_codes() {
+        let codes: Vec<_> = Extension::valid_codes().collect();
+
+        assert!(codes.contains(&(ARITHMETIC_CODE, ADD_CODE)));
+        assert!(codes.contains(&(ARITHMETIC_CODE, SUBTRACT_CODE)));
+        assert!(!Extension::is_valid_code(DATA_CODE, ADD_CODE));
+
+        for (extension, operation) in codes {
+            let decoded = Extension::from_codes(extension, operation).unwrap();
+
+            assert_eq!(decoded.code(), extension);
+            assert_eq!(decoded.operation_code(), operation);
+        }
+    }
+
+    #[test]
+    fn mnemonic() {
+        let mut mnemonics = Vec::new();
+
+        // Every valid pair of codes must survive a trip through its assembly name.
+        for extension_code in 0..64 {
+            for operation_code in 0..16 {
+                let extension = match Extension::from_codes(extension_code, operation_code) {
+                    Ok(extension) => extension,
+                    Err(_) => continue
+                };
+
+                assert_eq!(Extension::from_mnemonic(extension.mnemonic()), Some(extension.clone()));
+                assert!(!mnemonics.contains(&extension.mnemonic()));
+                mnemonics.push(extension.mnemonic());
+            }
+        }
 
-        // assert_eq!(operation_generic.expects_static(), Arithmetic::Add.expects_static());
+        assert!(Extension::from_mnemonic("unknown").is_none());
     }
 }
\ No newline at end of file
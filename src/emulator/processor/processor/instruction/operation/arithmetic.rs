@@ -1,37 +1,79 @@
-use crate::emulator::processor;
-use crate::emulator::processor::processor::instruction::Data;
-use crate::emulator::processor::processor::instruction::operand::OperandsPresence;
-use crate::emulator::processor::processor::instruction::operation::{Coded, Operation, OperationExecuteError};
+use crate::emulator::memory::MemoryAccess;
+use crate::emulator::processor::processor::{Condition, Core, Flags};
+use crate::number;
+use crate::emulator::processor::processor::instruction::Instruction;
+use crate::emulator::processor::processor::instruction::operand::{Destination, Operands, OperandsPresence, ResolvedOperand};
+use crate::emulator::processor::processor::instruction::operation::{Category, Coded, Operation, OperationExecuteError};
 
 // region: Constants
-pub const ADD_CODE     : u8 = 0;
-pub const SUBTRACT_CODE: u8 = 1;
+pub const ADD_CODE                   : u8 = 0;
+pub const SUBTRACT_CODE              : u8 = 1;
+pub const SET_IF_EQUAL_CODE          : u8 = 2;
+pub const SET_IF_LESS_CODE           : u8 = 3;
+pub const SET_IF_LESS_SIGNED_CODE    : u8 = 4;
+pub const SET_IF_GREATER_CODE        : u8 = 5;
+pub const SET_IF_GREATER_SIGNED_CODE : u8 = 6;
+pub const LOAD_EFFECTIVE_ADDRESS_CODE: u8 = 7;
+
+pub const ADD_MNEMONIC                   : &str = "add";
+pub const SUBTRACT_MNEMONIC              : &str = "sub";
+pub const SET_IF_EQUAL_MNEMONIC          : &str = "seq";
+pub const SET_IF_LESS_MNEMONIC           : &str = "slt";
+pub const SET_IF_LESS_SIGNED_MNEMONIC    : &str = "slts";
+pub const SET_IF_GREATER_MNEMONIC        : &str = "sgt";
+pub const SET_IF_GREATER_SIGNED_MNEMONIC : &str = "sgts";
+pub const LOAD_EFFECTIVE_ADDRESS_MNEMONIC: &str = "lea";
 // endregion
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum Arithmetic {
     #[default]
     Add,
-    Subtract
+    Subtract,
+    /// Store 1 if the operands are equal, otherwise 0.
+    SetIfEqual,
+    /// Store 1 if the static operand is less than the dynamic operand as unsigned numbers, otherwise 0.
+    SetIfLess,
+    /// Store 1 if the static operand is less than the dynamic operand as two's complement numbers, otherwise 0.
+    SetIfLessSigned,
+    /// Store 1 if the static operand is greater than the dynamic operand as unsigned numbers, otherwise 0.
+    SetIfGreater,
+    /// Store 1 if the static operand is greater than the dynamic operand as two's complement numbers, otherwise 0.
+    SetIfGreaterSigned,
+    /// Store the address the dynamic operand refers to in the static operand register without accessing memory.
+    LoadEffectiveAddress
 }
 
 impl<'a> Operation<'a> for Arithmetic {
-    fn execute(&mut self, _code: u8, _data: Option<&Data>, _context: &mut processor::processor::Context) -> Result<(), OperationExecuteError> {
-        // context.accumulator = 100;
-        // TODO
-        Ok(())
+    fn execute(&self, _code: u8, instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        match self {
+            Self::Add | Self::Subtract => self.execute_add_subtract(instruction, core, memory),
+            Self::SetIfEqual | Self::SetIfLess | Self::SetIfLessSigned | Self::SetIfGreater | Self::SetIfGreaterSigned =>
+                self.execute_set_if(instruction, core, memory),
+            Self::LoadEffectiveAddress => Self::load_effective_address(instruction, core, memory)
+        }
     }
 
-    fn get_presence(&mut self) -> Option<OperandsPresence> {
+    fn get_presence(&self) -> Option<OperandsPresence> {
         Some(OperandsPresence::AllPresent)
     }
+
+    fn category(&self) -> Category {
+        Category::Arithmetic
+    }
 }
 
 impl Coded<u8> for Arithmetic {
-    fn code(&mut self) -> u8 {
+    fn code(&self) -> u8 {
         match self {
-            Self::Add      => ADD_CODE,
-            Self::Subtract => SUBTRACT_CODE
+            Self::Add                  => ADD_CODE,
+            Self::Subtract             => SUBTRACT_CODE,
+            Self::SetIfEqual           => SET_IF_EQUAL_CODE,
+            Self::SetIfLess            => SET_IF_LESS_CODE,
+            Self::SetIfLessSigned      => SET_IF_LESS_SIGNED_CODE,
+            Self::SetIfGreater         => SET_IF_GREATER_CODE,
+            Self::SetIfGreaterSigned   => SET_IF_GREATER_SIGNED_CODE,
+            Self::LoadEffectiveAddress => LOAD_EFFECTIVE_ADDRESS_CODE
         }
     }
 }
@@ -39,9 +81,364 @@ impl Coded<u8> for Arithmetic {
 impl Arithmetic {
     pub fn from_code(code: u8) -> Option<Self> {
         Some(match code {
-            ADD_CODE      => Self::Add,
-            SUBTRACT_CODE => Self::Subtract,
+            ADD_CODE                    => Self::Add,
+            SUBTRACT_CODE               => Self::Subtract,
+            SET_IF_EQUAL_CODE           => Self::SetIfEqual,
+            SET_IF_LESS_CODE            => Self::SetIfLess,
+            SET_IF_LESS_SIGNED_CODE     => Self::SetIfLessSigned,
+            SET_IF_GREATER_CODE         => Self::SetIfGreater,
+            SET_IF_GREATER_SIGNED_CODE  => Self::SetIfGreaterSigned,
+            LOAD_EFFECTIVE_ADDRESS_CODE => Self::LoadEffectiveAddress,
+            _ => return None
+        })
+    }
+
+    /// Get the assembly name of this operation.
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::Add                  => ADD_MNEMONIC,
+            Self::Subtract             => SUBTRACT_MNEMONIC,
+            Self::SetIfEqual           => SET_IF_EQUAL_MNEMONIC,
+            Self::SetIfLess            => SET_IF_LESS_MNEMONIC,
+            Self::SetIfLessSigned      => SET_IF_LESS_SIGNED_MNEMONIC,
+            Self::SetIfGreater         => SET_IF_GREATER_MNEMONIC,
+            Self::SetIfGreaterSigned   => SET_IF_GREATER_SIGNED_MNEMONIC,
+            Self::LoadEffectiveAddress => LOAD_EFFECTIVE_ADDRESS_MNEMONIC
+        }
+    }
+
+    /// Create from the assembly name of an operation. [None] is returned if the name does not belong to an arithmetic
+    /// operation.
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        Some(match mnemonic {
+            ADD_MNEMONIC                    => Self::Add,
+            SUBTRACT_MNEMONIC               => Self::Subtract,
+            SET_IF_EQUAL_MNEMONIC           => Self::SetIfEqual,
+            SET_IF_LESS_MNEMONIC            => Self::SetIfLess,
+            SET_IF_LESS_SIGNED_MNEMONIC     => Self::SetIfLessSigned,
+            SET_IF_GREATER_MNEMONIC         => Self::SetIfGreater,
+            SET_IF_GREATER_SIGNED_MNEMONIC  => Self::SetIfGreaterSigned,
+            LOAD_EFFECTIVE_ADDRESS_MNEMONIC => Self::LoadEffectiveAddress,
             _ => return None
         })
     }
+
+    /// Read the values of both operands at the width of the instruction. The width is returned with them. Dynamic
+    /// operands in memory are read through a frame of the width, translated if the core is in virtual mode.
+    fn operand_values(instruction: &Instruction, core: &Core, memory: &mut impl MemoryAccess) -> Result<(number::Size, number::Data, number::Data), OperationExecuteError> {
+        let data = match &instruction.data {
+            Some(data) => data,
+            None => return Err(OperationExecuteError::Data(true))
+        };
+
+        let operands = match &data.operands {
+            Operands::AllPresent(operands) => operands,
+            _ => return Err(OperationExecuteError::Operand(OperandsPresence::AllPresent))
+        };
+
+        let registers = &core.context.registers;
+        let width = data.width.clone();
+
+        let x_static = match registers.read(operands.x_static, width.clone()) {
+            Ok(value) => value,
+            Err(error) => return Err(OperationExecuteError::Register(error))
+        };
+
+        let x_dynamic = match operands.x_dynamic.resolve(&core.context, width.clone()) {
+            Ok(ResolvedOperand::Register(register)) => match registers.read(register, width.clone()) {
+                Ok(value) => value,
+                Err(error) => return Err(OperationExecuteError::Register(error))
+            },
+            Ok(ResolvedOperand::Memory(frame)) => match memory.get(frame, core.context.virtual_mode) {
+                Ok(value) => value,
+                Err(error) => return Err(OperationExecuteError::Memory(error))
+            },
+            Ok(ResolvedOperand::Constant(constant)) => constant,
+            Err(error) => return Err(OperationExecuteError::Register(error))
+        };
+
+        Ok((width, x_static, x_dynamic))
+    }
+
+    /// Add the dynamic operand to or subtract it from the static operand with [Arithmetic::add_subtract]. The result is
+    /// written to the destination and the flags register is set from the flags of the result.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    /// use atln_processor::emulator::processor::processor::{CARRY_FLAG, Core, SIGN_FLAG, ZERO_FLAG};
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number;
+    ///
+    /// let mut core = Core::default();
+    /// let mut memory = Memory::from(vec![ 0, 0, 0, 0, 0, 0, 0, 0, 1, 0 ]);
+    /// core.context.registers.general[1] = 0xFF;
+    ///
+    /// // 1 + 0xFF as bytes carries out and leaves 0 in memory.
+    /// let add = Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Add),
+    ///     data: Some(Data {
+    ///         width: number::Size::Byte,
+    ///         destination: Destination::Dynamic,
+    ///         synchronous: false,
+    ///         operands: Operands::all(1, Dynamic::Memory(number::Data::Byte(8)))
+    ///     })
+    /// };
+    ///
+    /// add.extension.execute(&add, &mut core, &mut memory).unwrap();
+    /// assert_eq!(memory.bytes[8], 0);
+    /// assert_eq!(core.context.registers.flags, ZERO_FLAG | CARRY_FLAG);
+    ///
+    /// // 0xFF - 0x100 as words borrows. Only the word of the register is written.
+    /// core.context.registers.general[1] = 0xAAAA_0000_00FF;
+    /// let subtract = Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::Subtract),
+    ///     data: Some(Data {
+    ///         width: number::Size::Word,
+    ///         destination: Destination::Static,
+    ///         synchronous: false,
+    ///         operands: Operands::all(1, Dynamic::Constant(number::Data::Word(0x100)))
+    ///     })
+    /// };
+    ///
+    /// subtract.extension.execute(&subtract, &mut core, &mut memory).unwrap();
+    /// assert_eq!(core.context.registers.general[1], 0xAAAA_0000_FFFF);
+    /// assert_eq!(core.context.registers.flags, CARRY_FLAG | SIGN_FLAG);
+    /// ```
+    fn execute_add_subtract(&self, instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        let (width, x_static, x_dynamic) = Self::operand_values(instruction, core, memory)?;
+
+        // Unwrapping is safe because only add and subtract are executed here.
+        let (result, flags) = self.add_subtract(&x_static, &x_dynamic, width).unwrap();
+
+        if let Err(error) = core.store_result(instruction, result, memory) { return Err(OperationExecuteError::Store(error)) }
+        core.context.registers.flags = flags.bits();
+        Ok(())
+    }
+
+    /// Compare the operands with [Arithmetic::set_if] and write 1 or 0 to the destination. The flags are not changed.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    /// use atln_processor::emulator::processor::processor::Core;
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::Extension;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number;
+    ///
+    /// let mut core = Core::default();
+    /// let mut memory = Memory::default();
+    ///
+    /// // Compare 0xFF in r1 against 1 as bytes and store the result in r1.
+    /// let mut run = |operation: Arithmetic| {
+    ///     core.context.registers.general[1] = 0xFF;
+    ///     let instruction = Instruction {
+    ///         extension: Extension::Arithmetic(operation),
+    ///         data: Some(Data {
+    ///             width: number::Size::Byte,
+    ///             destination: Destination::Static,
+    ///             synchronous: false,
+    ///             operands: Operands::all(1, Dynamic::Constant(number::Data::Byte(0x01)))
+    ///         })
+    ///     };
+    ///
+    ///     instruction.extension.execute(&instruction, &mut core, &mut memory).unwrap();
+    ///     core.context.registers.general[1]
+    /// };
+    ///
+    /// // 255 is greater than 1, but -1 is less than 1.
+    /// assert_eq!(run(Arithmetic::SetIfLess), 0);
+    /// assert_eq!(run(Arithmetic::SetIfLessSigned), 1);
+    /// assert_eq!(run(Arithmetic::SetIfGreater), 1);
+    /// assert_eq!(run(Arithmetic::SetIfGreaterSigned), 0);
+    /// assert_eq!(run(Arithmetic::SetIfEqual), 0);
+    /// ```
+    fn execute_set_if(&self, instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        let (width, x_static, x_dynamic) = Self::operand_values(instruction, core, memory)?;
+
+        // Unwrapping is safe because only set if operations are executed here.
+        let result = self.set_if(&x_static, &x_dynamic, width).unwrap();
+
+        match core.store_result(instruction, result, memory) {
+            Ok(()) => Ok(()),
+            Err(error) => Err(OperationExecuteError::Store(error))
+        }
+    }
+
+    /// Store the address the dynamic operand refers to in the static operand register. The address is computed the same
+    /// way as when the operand is dereferenced, but memory is never accessed. The address is truncated to the width.
+    /// - If the dynamic operand does not refer to memory, then [Err(OperationExecuteError::Addressing)] is returned.
+    /// - If the destination is the dynamic operand, then [Err(OperationExecuteError::Destination)] is returned.
+    /// ```
+    /// use atln_processor::emulator::memory::Memory;
+    /// use atln_processor::emulator::processor::processor::Core;
+    /// use atln_processor::emulator::processor::processor::instruction::{Data, Instruction};
+    /// use atln_processor::emulator::processor::processor::instruction::operand::{Destination, Dynamic, Offset, Operands};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::{Extension, OperationExecuteError};
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number;
+    ///
+    /// let mut core = Core::default();
+    /// let mut memory = Memory::default();
+    /// core.context.registers.general[5] = 0x1000;
+    ///
+    /// let lea = |x_dynamic: Dynamic| Instruction {
+    ///     extension: Extension::Arithmetic(Arithmetic::LoadEffectiveAddress),
+    ///     data: Some(Data {
+    ///         width: number::Size::Quad,
+    ///         destination: Destination::Static,
+    ///         synchronous: false,
+    ///         operands: Operands::all(2, x_dynamic)
+    ///     })
+    /// };
+    ///
+    /// let offset = lea(Dynamic::Offset(Offset { register: 5, offset: number::Data::Word(0x24) }));
+    /// offset.extension.execute(&offset, &mut core, &mut memory).unwrap();
+    /// assert_eq!(core.context.registers.general[2], 0x1024);
+    ///
+    /// // A register has no address.
+    /// let register = lea(Dynamic::Register(5));
+    /// assert_eq!(register.extension.execute(&register, &mut core, &mut memory), Err(OperationExecuteError::Addressing));
+    /// ```
+    fn load_effective_address(instruction: &Instruction, core: &mut Core, memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        let data = match &instruction.data {
+            Some(data) => data,
+            None => return Err(OperationExecuteError::Data(true))
+        };
+
+        let operands = match &data.operands {
+            Operands::AllPresent(operands) => operands,
+            _ => return Err(OperationExecuteError::Operand(OperandsPresence::AllPresent))
+        };
+
+        if let Destination::Dynamic = data.destination { return Err(OperationExecuteError::Destination) }
+
+        let address = match operands.x_dynamic.resolve(&core.context, data.width.clone()) {
+            Ok(ResolvedOperand::Memory(frame)) => frame.address,
+            Ok(_) => return Err(OperationExecuteError::Addressing),
+            Err(error) => return Err(OperationExecuteError::Register(error))
+        };
+
+        match core.store_result(instruction, number::Data::Quad(address), memory) {
+            Ok(()) => Ok(()),
+            Err(error) => Err(OperationExecuteError::Store(error))
+        }
+    }
+
+    /// Get the condition a set if operation tests. The condition is evaluated against the flags of subtracting the
+    /// dynamic operand from the static operand. [None] is returned if this is not a set if operation.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::Condition;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    ///
+    /// assert_eq!(Arithmetic::SetIfLess.condition(), Some(Condition::Carry));
+    /// assert_eq!(Arithmetic::SetIfLessSigned.condition(), Some(Condition::Less));
+    /// assert_eq!(Arithmetic::Add.condition(), None);
+    /// ```
+    pub fn condition(&self) -> Option<Condition> {
+        Some(match self {
+            Self::SetIfEqual         => Condition::Zero,
+            Self::SetIfLess          => Condition::Carry,
+            Self::SetIfLessSigned    => Condition::Less,
+            Self::SetIfGreater       => Condition::Above,
+            Self::SetIfGreaterSigned => Condition::Greater,
+            _ => return None
+        })
+    }
+
+    /// Compute the result of a set if operation from the static and dynamic operand values. The operands are
+    /// subtracted at the width with [Arithmetic::add_subtract] and the [Arithmetic::condition] is evaluated against the
+    /// flags, so only the lower bytes of the operands that fit in the width are compared. The result is 1 or 0 at the
+    /// width. [None] is returned if this is not a set if operation.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// let x_static = Data::Byte(0xFF);
+    /// let x_dynamic = Data::Byte(0x01);
+    ///
+    /// // 255 is greater than 1, but -1 is less than 1.
+    /// assert!(matches!(Arithmetic::SetIfLess.set_if(&x_static, &x_dynamic, Size::Byte), Some(Data::Byte(0))));
+    /// assert!(matches!(Arithmetic::SetIfLessSigned.set_if(&x_static, &x_dynamic, Size::Byte), Some(Data::Byte(1))));
+    /// assert!(matches!(Arithmetic::SetIfGreater.set_if(&x_static, &x_dynamic, Size::Byte), Some(Data::Byte(1))));
+    /// assert!(matches!(Arithmetic::SetIfGreaterSigned.set_if(&x_static, &x_dynamic, Size::Byte), Some(Data::Byte(0))));
+    ///
+    /// // At a wider width 0xFF is positive.
+    /// assert!(matches!(Arithmetic::SetIfLessSigned.set_if(&x_static, &x_dynamic, Size::Word), Some(Data::Word(0))));
+    ///
+    /// // Bytes past the width are ignored.
+    /// assert!(matches!(Arithmetic::SetIfEqual.set_if(&Data::Word(0x0101), &x_dynamic, Size::Byte), Some(Data::Byte(1))));
+    /// assert!(Arithmetic::Add.set_if(&x_static, &x_dynamic, Size::Byte).is_none());
+    /// ```
+    pub fn set_if(&self, x_static: &number::Data, x_dynamic: &number::Data, width: number::Size) -> Option<number::Data> {
+        let condition = self.condition()?;
+
+        // Unwrapping is safe because subtracting always has a result.
+        let (_, flags) = Self::Subtract.add_subtract(x_static, x_dynamic, width.clone()).unwrap();
+        number::Data::from_exponent_selecting(width.exponent(), condition.evaluate(&flags) as u64)
+    }
+
+    /// Compute the result and flags of an add or subtract operation. The static operand is the left hand side. Only
+    /// the lower bytes of the operands that fit in the width are used, and the result is at the width. [None] is
+    /// returned if this is not an add or subtract operation.
+    /// ```
+    /// use atln_processor::emulator::processor::processor::Flags;
+    /// use atln_processor::emulator::processor::processor::instruction::operation::arithmetic::Arithmetic;
+    /// use atln_processor::number::{Data, Size};
+    ///
+    /// // Zero.
+    /// let (result, flags) = Arithmetic::Subtract.add_subtract(&Data::Byte(5), &Data::Byte(5), Size::Byte).unwrap();
+    /// assert!(matches!(result, Data::Byte(0)));
+    /// assert_eq!(flags, Flags { zero: true, ..Flags::default() });
+    ///
+    /// // Carry out of the width, which also gives zero.
+    /// let (_, flags) = Arithmetic::Add.add_subtract(&Data::Byte(0xFF), &Data::Byte(1), Size::Byte).unwrap();
+    /// assert_eq!(flags, Flags { zero: true, carry: true, ..Flags::default() });
+    ///
+    /// // Borrow and sign.
+    /// let (result, flags) = Arithmetic::Subtract.add_subtract(&Data::Word(1), &Data::Word(2), Size::Word).unwrap();
+    /// assert!(matches!(result, Data::Word(0xFFFF)));
+    /// assert_eq!(flags, Flags { carry: true, sign: true, ..Flags::default() });
+    ///
+    /// // Signed overflow. 127 + 1 does not fit in a signed byte.
+    /// let (_, flags) = Arithmetic::Add.add_subtract(&Data::Byte(0x7F), &Data::Byte(1), Size::Byte).unwrap();
+    /// assert_eq!(flags, Flags { sign: true, overflow: true, ..Flags::default() });
+    ///
+    /// // -128 - 1 does not fit either.
+    /// let (_, flags) = Arithmetic::Subtract.add_subtract(&Data::Byte(0x80), &Data::Byte(1), Size::Byte).unwrap();
+    /// assert_eq!(flags, Flags { overflow: true, ..Flags::default() });
+    ///
+    /// // Quad carries out of the full register.
+    /// let (_, flags) = Arithmetic::Add.add_subtract(&Data::Quad(u64::MAX), &Data::Quad(2), Size::Quad).unwrap();
+    /// assert!(flags.carry);
+    ///
+    /// assert!(Arithmetic::SetIfEqual.add_subtract(&Data::Byte(0), &Data::Byte(0), Size::Byte).is_none());
+    /// ```
+    pub fn add_subtract(&self, x_static: &number::Data, x_dynamic: &number::Data, width: number::Size) -> Option<(number::Data, Flags)> {
+        let bits = width.size() as u32 * 8;
+        let mask = u64::MAX >> (64 - bits);
+        let sign = 1u64 << (bits - 1);
+
+        let left = x_static.quad() & mask;
+        let right = x_dynamic.quad() & mask;
+
+        let (result, carry, overflow) = match self {
+            Self::Add => {
+                let (sum, carried) = left.overflowing_add(right);
+                let result = sum & mask;
+                (result, carried || sum > mask, (left ^ result) & (right ^ result) & sign != 0)
+            },
+            Self::Subtract => {
+                let result = left.wrapping_sub(right) & mask;
+                (result, left < right, (left ^ right) & (left ^ result) & sign != 0)
+            },
+            _ => return None
+        };
+
+        // Unwrapping is safe because every size has a valid exponent.
+        let result = number::Data::from_exponent_selecting(width.exponent(), result).unwrap();
+        let flags = Flags::from_arithmetic(&result, width, carry, overflow);
+        Some((result, flags))
+    }
 }
\ No newline at end of file
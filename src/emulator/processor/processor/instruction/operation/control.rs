@@ -0,0 +1,90 @@
+use crate::emulator::memory::MemoryAccess;
+use crate::emulator::processor::processor::Core;
+use crate::emulator::processor::processor::instruction::Instruction;
+use crate::emulator::processor::processor::instruction::operand::OperandsPresence;
+use crate::emulator::processor::processor::instruction::operation::{Category, Coded, Operation, OperationExecuteError};
+
+// region: Constants
+pub const NO_OPERATION_CODE: u8 = 0;
+pub const HALT_CODE        : u8 = 1;
+
+pub const NO_OPERATION_MNEMONIC: &str = "nop";
+pub const HALT_MNEMONIC        : &str = "hlt";
+// endregion
+
+/// Operations which control the flow of execution.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Control {
+    /// Do nothing. Used for padding and alignment in code. With no operands it always encodes to the 2 driver bytes
+    /// [NO_OPERATION_ENCODED].
+    ///
+    /// [NO_OPERATION_ENCODED]: crate::emulator::processor::processor::instruction::operation::NO_OPERATION_ENCODED
+    #[default]
+    NoOperation,
+    /// Stop the core. [Core::run] returns once the core is halted.
+    ///
+    /// [Core::run]: crate::emulator::processor::processor::Core::run
+    Halt
+}
+
+impl<'a> Operation<'a> for Control {
+    /// The processor moves past the instruction as with any other, so a no operation has nothing left to do. A halt
+    /// sets [Core::halted] so [Core::run] stops after it. Neither operation takes data.
+    fn execute(&self, _code: u8, instruction: &Instruction, core: &mut Core, _memory: &mut impl MemoryAccess) -> Result<(), OperationExecuteError> {
+        if instruction.data.is_some() { return Err(OperationExecuteError::Data(false)) }
+
+        match self {
+            Self::NoOperation => {},
+            Self::Halt => core.halted = true
+        }
+
+        Ok(())
+    }
+
+    fn get_presence(&self) -> Option<OperandsPresence> {
+        match self {
+            Self::NoOperation | Self::Halt => None
+        }
+    }
+
+    fn category(&self) -> Category {
+        Category::ControlFlow
+    }
+}
+
+impl Coded<u8> for Control {
+    fn code(&self) -> u8 {
+        match self {
+            Self::NoOperation => NO_OPERATION_CODE,
+            Self::Halt => HALT_CODE
+        }
+    }
+}
+
+impl Control {
+    pub fn from_code(code: u8) -> Option<Self> {
+        Some(match code {
+            NO_OPERATION_CODE => Self::NoOperation,
+            HALT_CODE => Self::Halt,
+            _ => return None
+        })
+    }
+
+    /// Get the assembly name of this operation.
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::NoOperation => NO_OPERATION_MNEMONIC,
+            Self::Halt => HALT_MNEMONIC
+        }
+    }
+
+    /// Create from the assembly name of an operation. [None] is returned if the name does not belong to a control
+    /// operation.
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        Some(match mnemonic {
+            NO_OPERATION_MNEMONIC => Self::NoOperation,
+            HALT_MNEMONIC => Self::Halt,
+            _ => return None
+        })
+    }
+}
\ No newline at end of file
@@ -34,6 +34,16 @@ impl<T> ArrayBounds for [T] {
 
 // Implementations
 
+/// Order of bytes when a number is stored as multiple bytes.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first.
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big
+}
+
 /// Absolute modes.
 /// Base type variants for representing an absolute value.
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -46,6 +56,14 @@ pub enum Size {
 }
 
 impl Size {
+    /// Every variant ordered from smallest to largest.
+    /// ```
+    /// use atln_processor::number::Size;
+    ///
+    /// assert!(Size::ALL.windows(2).all(|pair| pair[0].size() < pair[1].size()));
+    /// ```
+    pub const ALL: [Size; 4] = [Size::Byte, Size::Word, Size::Dual, Size::Quad];
+
     /// Number of bytes the current variant holds. The uint size.
     pub fn size(&self) -> u8 {
         match self {
@@ -93,6 +111,22 @@ impl Size {
             Self::Quad => IMMEDIATE_EXPONENT_QUAD
         }
     }
+
+    /// The smallest size that holds the value as an unsigned number.
+    pub fn minimum(value: u64) -> Self {
+        if value <= u8::MAX as u64 { Self::Byte }
+        else if value <= u16::MAX as u64 { Self::Word }
+        else if value <= u32::MAX as u64 { Self::Dual }
+        else { Self::Quad }
+    }
+
+    /// The smallest size that holds the value as a two's complement number.
+    pub fn minimum_signed(value: i64) -> Self {
+        if value >= i8::MIN as i64 && value <= i8::MAX as i64 { Self::Byte }
+        else if value >= i16::MIN as i64 && value <= i16::MAX as i64 { Self::Word }
+        else if value >= i32::MIN as i64 && value <= i32::MAX as i64 { Self::Dual }
+        else { Self::Quad }
+    }
 }
 
 /// Variable absolute data type.
@@ -107,6 +141,39 @@ pub enum Data {
 }
 
 impl Data {
+    /// Create data of the narrowest variant that holds the value. This is the same as [Data::from_quad_selecting].
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert!(matches!(Data::minimal(0), Data::Byte(0)));
+    /// assert!(matches!(Data::minimal(255), Data::Byte(255)));
+    /// assert!(matches!(Data::minimal(256), Data::Word(256)));
+    /// assert!(matches!(Data::minimal(65535), Data::Word(65535)));
+    /// assert!(matches!(Data::minimal(65536), Data::Dual(65536)));
+    /// assert!(matches!(Data::minimal(u32::MAX as u64), Data::Dual(u32::MAX)));
+    /// assert!(matches!(Data::minimal(u32::MAX as u64 + 1), Data::Quad(0x1_0000_0000)));
+    /// ```
+    pub fn minimal(value: u64) -> Self {
+        Self::from_quad_selecting(value)
+    }
+
+    /// Create data of the narrowest variant that holds the value as a two's complement number, see
+    /// [Size::minimum_signed]. The variant holds the bits of the value at its width.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert!(matches!(Data::minimal_signed(127), Data::Byte(0x7F)));
+    /// assert!(matches!(Data::minimal_signed(-128), Data::Byte(0x80)));
+    /// assert!(matches!(Data::minimal_signed(128), Data::Word(0x0080)));
+    /// assert!(matches!(Data::minimal_signed(-129), Data::Word(0xFF7F)));
+    /// assert!(matches!(Data::minimal_signed(i16::MIN as i64 - 1), Data::Dual(0xFFFF_7FFF)));
+    /// assert!(matches!(Data::minimal_signed(i32::MIN as i64 - 1), Data::Quad(0xFFFF_FFFF_7FFF_FFFF)));
+    /// ```
+    pub fn minimal_signed(value: i64) -> Self {
+        // Unwrapping is safe because every size has a valid exponent.
+        Self::from_exponent_selecting(Size::minimum_signed(value).exponent(), value as u64).unwrap()
+    }
+
     pub fn to_le_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
 
@@ -119,6 +186,66 @@ impl Data {
 
         bytes
     }
+
+    /// Get the bytes of the number with the most significant byte first. This is [Data::to_le_bytes] reversed.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert_eq!(Data::Dual(0x01020304).to_be_bytes(), vec![ 1, 2, 3, 4 ]);
+    /// assert_eq!(Data::Dual(0x01020304).to_le_bytes(), vec![ 4, 3, 2, 1 ]);
+    /// ```
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.to_le_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Get the bytes of the number in the given byte order.
+    /// ```
+    /// use atln_processor::number::{Data, Endianness};
+    ///
+    /// assert_eq!(Data::Word(0x0102).to_bytes(Endianness::Big), vec![0x01, 0x02]);
+    /// assert_eq!(Data::Word(0x0102).to_bytes(Endianness::Little), vec![0x02, 0x01]);
+    /// ```
+    pub fn to_bytes(&self, endianness: Endianness) -> Vec<u8> {
+        match endianness {
+            Endianness::Little => self.to_le_bytes(),
+            Endianness::Big => self.to_be_bytes()
+        }
+    }
+
+    /// Create a number from little endian bytes. The variant is selected by the number of bytes, so [None] is
+    /// returned if the length is not the size of a variant.
+    pub fn from_le_bytes(bytes: &[u8]) -> Option<Self> {
+        let size = Size::from_size(bytes.len())?;
+        let mut quad_buffer = [0u8; QUAD_SIZE];
+        quad_buffer[0..bytes.len()].copy_from_slice(bytes);
+
+        Self::from_exponent_selecting(size.exponent(), u64::from_le_bytes(quad_buffer))
+    }
+
+    /// Create a number from big endian bytes. The variant is selected by the number of bytes, so [None] is returned if
+    /// the length is not the size of a variant.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert!(matches!(Data::from_be_bytes(&[0x01, 0x02]), Some(Data::Word(0x0102))));
+    /// assert!(matches!(Data::from_le_bytes(&[0x01, 0x02]), Some(Data::Word(0x0201))));
+    /// assert!(Data::from_be_bytes(&[0x01, 0x02, 0x03]).is_none());
+    /// ```
+    pub fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reversed = bytes.to_vec();
+        reversed.reverse();
+        Self::from_le_bytes(&reversed)
+    }
+
+    /// Create a number from bytes in the given byte order. See [Data::from_le_bytes] and [Data::from_be_bytes].
+    pub fn from_bytes(bytes: &[u8], endianness: Endianness) -> Option<Self> {
+        match endianness {
+            Endianness::Little => Self::from_le_bytes(bytes),
+            Endianness::Big => Self::from_be_bytes(bytes)
+        }
+    }
     
     pub fn exponent(self) -> u8 {
         Size::from(self).exponent()
@@ -168,6 +295,86 @@ impl Data {
             Self::Quad(_) => QUAD_SIZE as u8
         }
     }
+
+    /// Store a wide result into the variant of this number, returning [None] if the result does not fit.
+    fn fit_checked(&self, result: u128) -> Option<Self> {
+        let fitted = Self::from_exponent_selecting(self.clone().exponent(), result as u64).unwrap();
+        if fitted.quad() as u128 != result { return None }
+        Some(fitted)
+    }
+
+    /// Add another number to this one. The result has the same variant as this number and wraps around at its width.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert_eq!(Data::Byte(255).wrapping_add(&Data::Byte(1)), Data::Byte(0));
+    /// assert_eq!(Data::Word(255).wrapping_add(&Data::Byte(1)), Data::Word(256));
+    /// assert_eq!(Data::Quad(u64::MAX).wrapping_add(&Data::Quad(2)), Data::Quad(1));
+    /// assert!(matches!(Data::Dual(1).wrapping_add(&Data::Byte(1)), Data::Dual(2)));
+    /// ```
+    pub fn wrapping_add(&self, other: &Self) -> Self {
+        Self::from_exponent_selecting(self.clone().exponent(), self.quad().wrapping_add(other.quad())).unwrap()
+    }
+
+    /// Subtract another number from this one. The result has the same variant as this number and wraps around at its
+    /// width.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert_eq!(Data::Byte(0).wrapping_sub(&Data::Byte(1)), Data::Byte(255));
+    /// assert_eq!(Data::Word(0).wrapping_sub(&Data::Byte(1)), Data::Word(u16::MAX));
+    /// assert_eq!(Data::Dual(10).wrapping_sub(&Data::Dual(3)), Data::Dual(7));
+    /// ```
+    pub fn wrapping_sub(&self, other: &Self) -> Self {
+        Self::from_exponent_selecting(self.clone().exponent(), self.quad().wrapping_sub(other.quad())).unwrap()
+    }
+
+    /// Multiply this number by another. The result has the same variant as this number and wraps around at its width.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert_eq!(Data::Byte(16).wrapping_mul(&Data::Byte(16)), Data::Byte(0));
+    /// assert_eq!(Data::Word(16).wrapping_mul(&Data::Byte(16)), Data::Word(256));
+    /// assert_eq!(Data::Dual(u32::MAX).wrapping_mul(&Data::Dual(2)), Data::Dual(u32::MAX - 1));
+    /// ```
+    pub fn wrapping_mul(&self, other: &Self) -> Self {
+        Self::from_exponent_selecting(self.clone().exponent(), self.quad().wrapping_mul(other.quad())).unwrap()
+    }
+
+    /// Add another number to this one. [None] is returned if the result does not fit in the variant of this number.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert_eq!(Data::Byte(254).checked_add(&Data::Byte(1)), Some(Data::Byte(255)));
+    /// assert_eq!(Data::Byte(255).checked_add(&Data::Byte(1)), None);
+    /// assert_eq!(Data::Quad(u64::MAX).checked_add(&Data::Byte(1)), None);
+    /// ```
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.fit_checked(self.quad() as u128 + other.quad() as u128)
+    }
+
+    /// Subtract another number from this one. [None] is returned if the result would be negative.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert_eq!(Data::Word(1).checked_sub(&Data::Byte(1)), Some(Data::Word(0)));
+    /// assert_eq!(Data::Word(0).checked_sub(&Data::Byte(1)), None);
+    /// ```
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.fit_checked(self.quad().checked_sub(other.quad())? as u128)
+    }
+
+    /// Multiply this number by another. [None] is returned if the result does not fit in the variant of this number.
+    /// ```
+    /// use atln_processor::number::Data;
+    ///
+    /// assert_eq!(Data::Byte(15).checked_mul(&Data::Byte(17)), Some(Data::Byte(255)));
+    /// assert_eq!(Data::Byte(16).checked_mul(&Data::Byte(16)), None);
+    /// assert_eq!(Data::Quad(u64::MAX).checked_mul(&Data::Byte(2)), None);
+    /// ```
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        self.fit_checked(self.quad() as u128 * other.quad() as u128)
+    }
 }
 
 // region: Converting numbers to data instances